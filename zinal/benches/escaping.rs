@@ -0,0 +1,91 @@
+use std::{borrow::Cow, collections::HashMap, fmt::Write as _, sync::OnceLock};
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use zinal::{html::HtmlEscaper, Escaper, Template};
+
+const PLAIN: &str = "The quick brown fox jumps over the lazy dog, again and again and again.";
+const SPECIAL: &str = "<a href=\"/?q=fox&dog\">The 'quick' brown fox</a> & the <lazy> dog";
+
+/// The previous implementation, looking up every character in a map and
+/// returning a copy whenever a character had to be escaped.
+fn escape_with_map(value: &str) -> Cow<'_, str> {
+    static ESCAPES: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+    let escapes = ESCAPES.get_or_init(|| {
+        HashMap::from([
+            ('<', "&lt;"),
+            ('>', "&gt;"),
+            ('&', "&amp;"),
+            ('\'', "&apos;"),
+            ('"', "&quot;"),
+        ])
+    });
+
+    let mut escaped = String::new();
+    let mut previous_offset = 0;
+
+    for (offset, c) in value.char_indices() {
+        if let Some(replacement) = escapes.get(&c) {
+            escaped.push_str(&value[previous_offset..offset]);
+            escaped.push_str(replacement);
+            previous_offset = offset + c.len_utf8();
+        }
+    }
+
+    if escaped.is_empty() {
+        return Cow::Borrowed(value);
+    }
+
+    escaped.push_str(&value[previous_offset..]);
+    Cow::Owned(escaped)
+}
+
+fn escaper(c: &mut Criterion) {
+    let mut group = c.benchmark_group("html_escaper");
+
+    for (name, input) in [("plain", PLAIN), ("special", SPECIAL)] {
+        group.bench_with_input(BenchmarkId::new("map", name), input, |b, input| {
+            let mut output = String::with_capacity(256);
+            b.iter(|| {
+                output.clear();
+                write!(output, "{}", escape_with_map(black_box(input))).unwrap();
+            });
+        });
+
+        group.bench_with_input(BenchmarkId::new("scanner", name), input, |b, input| {
+            let mut output = String::with_capacity(256);
+            b.iter(|| {
+                output.clear();
+                HtmlEscaper.escape(black_box(input), &mut output).unwrap();
+            });
+        });
+    }
+
+    group.finish();
+}
+
+#[derive(Template)]
+#[template(
+    "<table><#for row in self.rows#><tr><td>{{ row.0 }}</td><td>{{ row.1 }}</td><td>{{ row.2 }}</td></tr><#end#></table>"
+)]
+struct Table<'a> {
+    rows: &'a [(u32, &'a str, &'a str)],
+}
+
+fn table(c: &mut Criterion) {
+    let rows = (0..1000)
+        .map(|i| (i, PLAIN, if i % 10 == 0 { SPECIAL } else { PLAIN }))
+        .collect::<Vec<_>>();
+
+    c.bench_function("render_table", |b| {
+        b.iter(|| {
+            Table {
+                rows: black_box(&rows),
+            }
+            .render_to_string()
+            .unwrap()
+        });
+    });
+}
+
+criterion_group!(benches, escaper, table);
+criterion_main!(benches);
@@ -0,0 +1,183 @@
+use std::fmt::{self, Write as _};
+
+use crate::{Escaper, EscapingWriter, NoEscaper, RenderError, Renderable};
+
+/// Renders a value using its [fmt::Display] implementation, escaping the output.
+///
+/// The value is escaped while it is formatted, without formatting it into a
+/// string first.
+///
+/// # Example
+/// ```rust
+/// use std::net::Ipv4Addr;
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("<p>{{ Display(&self.address) }}</p>")]
+/// struct Address {
+///   address: Ipv4Addr,
+/// }
+///
+/// let address = Address { address: Ipv4Addr::LOCALHOST };
+///
+/// assert_eq!("<p>127.0.0.1</p>".to_owned(), address.render_to_string().unwrap());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Display<T>(pub T);
+
+impl<T: fmt::Display> Renderable for Display<T> {
+    fn render(
+        &self,
+        writer: &mut dyn fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        write!(EscapingWriter::new(escaper, writer), "{}", self.0)?;
+        Ok(())
+    }
+}
+
+/// Renders a value using its [fmt::Debug] implementation, escaping the output.
+///
+/// # Example
+/// ```rust
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("<pre>{{ Debug(&self.tags) }}</pre>")]
+/// struct Tags {
+///   tags: Vec<&'static str>,
+/// }
+///
+/// let tags = Tags { tags: vec!["a", "b"] };
+///
+/// assert_eq!(
+///   "<pre>[&quot;a&quot;, &quot;b&quot;]</pre>".to_owned(),
+///   tags.render_to_string().unwrap()
+/// );
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Debug<T>(pub T);
+
+impl<T: fmt::Debug> Renderable for Debug<T> {
+    fn render(
+        &self,
+        writer: &mut dyn fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        write!(EscapingWriter::new(escaper, writer), "{:?}", self.0)?;
+        Ok(())
+    }
+}
+
+/// Renders trusted markup as is, without escaping it.
+///
+/// The value is rendered with the [NoEscaper], so any [Renderable] can be
+/// wrapped, like `Option<String>`. Only use this for content that is known to
+/// be safe, like sanitized HTML.
+/// Templates can also render expressions without escaping with `{{! expr }}`,
+/// which wraps the value in `PreEscaped`.
+///
+/// # Example
+/// ```rust
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("<article>{{ PreEscaped(&self.body) }}{{! self.footer }}</article>")]
+/// struct Article {
+///   body: String,
+///   footer: &'static str,
+/// }
+///
+/// let article = Article {
+///   body: String::from("<p>Hello</p>"),
+///   footer: "<hr>",
+/// };
+///
+/// assert_eq!("<article><p>Hello</p><hr></article>".to_owned(), article.render_to_string().unwrap());
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct PreEscaped<T>(pub T);
+
+impl<T: Renderable> Renderable for PreEscaped<T> {
+    fn render(
+        &self,
+        writer: &mut dyn fmt::Write,
+        _escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        self.0.render(writer, &NoEscaper)
+    }
+}
+
+/// Render the elements of the iterator separated by the separator.
+///
+/// Both the elements and the separator are escaped. It can also be used as
+/// `join` filter, like `{{ self.tags | join(", ") }}`, and formats the elements
+/// and the separator if they implement [fmt::Display].
+///
+/// # Example
+/// ```rust
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("<p>{{ join(&self.names, \" & \") }}</p>")]
+/// struct Names {
+///   names: Vec<&'static str>,
+/// }
+///
+/// let names = Names { names: vec!["Mary", "John"] };
+///
+/// assert_eq!("<p>Mary &amp; John</p>".to_owned(), names.render_to_string().unwrap());
+/// ```
+pub fn join<I, S>(iter: I, separator: S) -> Join<I, S>
+where
+    I: IntoIterator + Clone,
+{
+    Join { iter, separator }
+}
+
+/// A Renderable that renders elements with a separator, see [join()].
+#[derive(Debug, Clone)]
+pub struct Join<I, S> {
+    iter: I,
+    separator: S,
+}
+
+impl<I, S> Renderable for Join<I, S>
+where
+    I: IntoIterator + Clone,
+    I::Item: Renderable,
+    S: Renderable,
+{
+    fn render(
+        &self,
+        writer: &mut dyn fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        for (index, element) in self.iter.clone().into_iter().enumerate() {
+            if index > 0 {
+                self.separator.render(writer, escaper)?;
+            }
+            element.render(writer, escaper)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<I, S> fmt::Display for Join<I, S>
+where
+    I: IntoIterator + Clone,
+    I::Item: fmt::Display,
+    S: fmt::Display,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, element) in self.iter.clone().into_iter().enumerate() {
+            if index > 0 {
+                write!(f, "{}", self.separator)?;
+            }
+            write!(f, "{}", element)?;
+        }
+
+        Ok(())
+    }
+}
@@ -17,7 +17,7 @@ use std::marker::PhantomData;
 /// to implement your attribute setters, automatically managing
 /// the token for you.
 ///
-/// In your build() method you can then take the values from
+/// In your try_build() method you can then take the values from
 /// the TemplateBuilder to construct your template.
 pub struct TemplateBuilder<Values, Token> {
     /// This builders current template values.
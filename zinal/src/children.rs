@@ -1,4 +1,4 @@
-use crate::{Context, Escaper};
+use crate::{Context, Escaper, RenderError};
 
 /// Represents child content to a template.
 pub trait Children {
@@ -8,19 +8,32 @@ pub trait Children {
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
         context: &Context,
-    ) -> Result<(), std::fmt::Error>;
+    ) -> Result<(), RenderError>;
+
+    /// Render the named slot to the given writer.
+    ///
+    /// Children without named slots render nothing.
+    fn render_slot(
+        &self,
+        _name: &str,
+        _writer: &mut dyn std::fmt::Write,
+        _escaper: &dyn Escaper,
+        _context: &Context,
+    ) -> Result<(), RenderError> {
+        Ok(())
+    }
 }
 
 impl<F> Children for F
 where
-    F: Fn(&mut dyn std::fmt::Write, &dyn Escaper, &Context) -> Result<(), std::fmt::Error>,
+    F: Fn(&mut dyn std::fmt::Write, &dyn Escaper, &Context) -> Result<(), RenderError>,
 {
     fn render(
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
         context: &Context,
-    ) -> Result<(), std::fmt::Error> {
+    ) -> Result<(), RenderError> {
         (self)(writer, escaper, context)
     }
 }
@@ -34,7 +47,66 @@ impl Children for EmptyChildren {
         _writer: &mut dyn std::fmt::Write,
         _escaper: &dyn Escaper,
         _context: &Context,
-    ) -> Result<(), std::fmt::Error> {
+    ) -> Result<(), RenderError> {
         Ok(())
     }
 }
+
+/// Child content of a template, consisting of the anonymous children and named slots.
+///
+/// Derived templates use this to pass `<:name>...</:name>` sections to a child template,
+/// which can then render them with `{{@slot name}}`.
+pub struct Slots<'a, C, const N: usize> {
+    children: C,
+    slots: [Slot<'a>; N],
+}
+
+/// A single named slot.
+pub struct Slot<'a> {
+    name: &'a str,
+    children: &'a dyn Children,
+}
+
+impl<'a, C, const N: usize> Slots<'a, C, N>
+where
+    C: Children,
+{
+    /// Create new children with the given anonymous children and named slots.
+    pub fn new(children: C, slots: [Slot<'a>; N]) -> Self {
+        Self { children, slots }
+    }
+}
+
+impl<'a> Slot<'a> {
+    /// Create a new slot with the given name and content.
+    pub fn new(name: &'a str, children: &'a dyn Children) -> Self {
+        Self { name, children }
+    }
+}
+
+impl<C, const N: usize> Children for Slots<'_, C, N>
+where
+    C: Children,
+{
+    fn render(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        escaper: &dyn Escaper,
+        context: &Context,
+    ) -> Result<(), RenderError> {
+        self.children.render(writer, escaper, context)
+    }
+
+    fn render_slot(
+        &self,
+        name: &str,
+        writer: &mut dyn std::fmt::Write,
+        escaper: &dyn Escaper,
+        context: &Context,
+    ) -> Result<(), RenderError> {
+        match self.slots.iter().find(|s| s.name == name) {
+            Some(slot) => slot.children.render(writer, escaper, context),
+            None => Ok(()),
+        }
+    }
+}
@@ -34,6 +34,23 @@ impl<'a> Context<'a> {
         self.params.insert(type_id, value);
     }
 
+    /// Mark a component boundary, where output rendered so far is sent to the
+    /// client when rendering to a stream.
+    ///
+    /// Derived templates call this before rendering a child template.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the stream was dropped.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    pub fn flush(&self) -> std::fmt::Result {
+        match self.get_param::<crate::stream::Flush>() {
+            Some(flush) => flush.flush(),
+            None => Ok(()),
+        }
+    }
+
     /// Extend this context with a child context.
     pub fn extend(&'a self, context: Context) -> Self {
         Self {
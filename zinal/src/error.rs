@@ -0,0 +1,418 @@
+use std::{
+    cell::{Cell, RefCell},
+    error::Error,
+    fmt,
+    io::Write as _,
+};
+
+/// The error returned when rendering a template fails.
+///
+/// Any error can be converted into a `RenderError`, so `?` can be used with
+/// arbitrary error types in template expressions and statements. While the
+/// error is passed up through the templates being rendered, it records the
+/// template components and the location in the template where it occurred.
+///
+/// # Example
+/// ```rust
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("<p>{{ self.count.parse::<u32>()? }}</p>")]
+/// struct Count<'a> {
+///   count: &'a str,
+/// }
+///
+/// let error = Count { count: "many" }.render_to_string().unwrap_err();
+///
+/// assert_eq!(&["Count"], error.components());
+/// assert_eq!("invalid digit found in string", error.error().to_string());
+/// ```
+pub struct RenderError {
+    error: Box<dyn Error + Send + Sync>,
+    components: Vec<&'static str>,
+    location: Option<Location>,
+}
+
+impl RenderError {
+    /// Create a new error from the given error or message.
+    pub fn new(error: impl Into<Box<dyn Error + Send + Sync>>) -> Self {
+        RenderError {
+            error: error.into(),
+            components: Vec::new(),
+            location: None,
+        }
+    }
+
+    /// The error that caused rendering to fail.
+    pub fn error(&self) -> &(dyn Error + Send + Sync + 'static) {
+        self.error.as_ref()
+    }
+
+    /// Consume this error and return the error that caused rendering to fail.
+    pub fn into_error(self) -> Box<dyn Error + Send + Sync> {
+        self.error
+    }
+
+    /// The names of the templates that were being rendered when the error
+    /// occurred, starting with the outermost template.
+    pub fn components(&self) -> &[&'static str] {
+        &self.components
+    }
+
+    /// The location in the template where the error occurred, if known.
+    pub fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    /// The templates that were being rendered and the location of the error,
+    /// displayed like `Page > Layout > UserCard (templates/user_card.html:12:5)`.
+    pub fn component_stack(&self) -> ComponentStack<'_> {
+        ComponentStack {
+            components: &self.components,
+            location: self.location.as_ref(),
+        }
+    }
+
+    /// Record that the error was passed up through the named template, while
+    /// rendering the item at the given location.
+    ///
+    /// Derived templates call this when rendering fails. The location is only
+    /// recorded by the innermost template.
+    pub fn in_component(mut self, name: &'static str, location: Option<Location>) -> Self {
+        self.components.insert(0, name);
+        self.at(location)
+    }
+
+    /// Record the location in the template where the error occurred, unless a
+    /// more precise location was recorded already.
+    pub fn at(mut self, location: Option<Location>) -> Self {
+        if self.location.is_none() {
+            self.location = location;
+        }
+        self
+    }
+}
+
+impl<E: Error + Send + Sync + 'static> From<E> for RenderError {
+    fn from(error: E) -> Self {
+        RenderError::new(error)
+    }
+}
+
+impl fmt::Display for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.error)?;
+
+        if !self.components.is_empty() || self.location.is_some() {
+            write!(f, ", in {}", self.component_stack())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Debug for RenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RenderError")
+            .field("error", &self.error)
+            .field("components", &self.components)
+            .field("location", &self.location)
+            .finish()
+    }
+}
+
+impl From<RenderError> for Box<dyn Error + Send + Sync> {
+    fn from(error: RenderError) -> Self {
+        Box::new(BoxedRenderError(error))
+    }
+}
+
+impl From<RenderError> for std::io::Error {
+    fn from(error: RenderError) -> Self {
+        std::io::Error::other(error)
+    }
+}
+
+/// Makes a [RenderError] usable as [Error], which it can't implement itself
+/// without conflicting with the conversion from any error.
+struct BoxedRenderError(RenderError);
+
+impl fmt::Display for BoxedRenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+impl fmt::Debug for BoxedRenderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(&self.0, f)
+    }
+}
+
+impl Error for BoxedRenderError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(self.0.error.as_ref())
+    }
+}
+
+/// An http body that fails with a render error, so that the output of a
+/// template that could not be rendered is not taken for a complete response.
+///
+/// Derived templates use this to convert into an axum body without panicking.
+#[cfg(feature = "axum")]
+#[doc(hidden)]
+pub struct RenderErrorBody(Option<RenderError>);
+
+#[cfg(feature = "axum")]
+impl RenderErrorBody {
+    /// Create a body that fails with the given error.
+    pub fn new(error: RenderError) -> Self {
+        RenderErrorBody(Some(error))
+    }
+}
+
+#[cfg(feature = "axum")]
+impl http_body::Body for RenderErrorBody {
+    type Data = bytes::Bytes;
+    type Error = RenderError;
+
+    fn poll_frame(
+        mut self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Result<http_body::Frame<Self::Data>, Self::Error>>> {
+        std::task::Poll::Ready(self.0.take().map(Err))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.0.is_none()
+    }
+}
+
+/// The error returned when a template is built without a context parameter
+/// that one of its `#[from_context]` fields requires.
+///
+/// Fields of type `Option<&T>` and fields with `#[from_context(default = ...)]`
+/// don't require the parameter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingContextParam {
+    /// The name of the template.
+    pub template: &'static str,
+    /// The name of the field taking the parameter.
+    pub field: &'static str,
+}
+
+impl fmt::Display for MissingContextParam {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Missing context parameter {} of template {}",
+            self.field, self.template
+        )
+    }
+}
+
+impl Error for MissingContextParam {}
+
+/// A breadcrumb of the templates being rendered, see [RenderError::component_stack()].
+#[derive(Debug, Clone, Copy)]
+pub struct ComponentStack<'a> {
+    components: &'a [&'static str],
+    location: Option<&'a Location>,
+}
+
+impl ComponentStack<'_> {
+    /// The names of the templates, starting with the outermost template.
+    pub fn components(&self) -> &[&'static str] {
+        self.components
+    }
+
+    /// The location in the innermost template, if known.
+    pub fn location(&self) -> Option<&Location> {
+        self.location
+    }
+}
+
+impl fmt::Display for ComponentStack<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.components.join(" > "))?;
+
+        match (self.components.is_empty(), self.location) {
+            (true, Some(location)) => write!(f, "{}", location),
+            (false, Some(location)) => write!(f, " ({})", location),
+            (_, None) => Ok(()),
+        }
+    }
+}
+
+thread_local! {
+    /// The number of templates currently being rendered on this thread.
+    static DEPTH: Cell<usize> = const { Cell::new(0) };
+
+    /// The component stack of a panic, collected while unwinding.
+    static PANIC_STACK: RefCell<Option<PanicStack>> = const { RefCell::new(None) };
+}
+
+struct PanicStack {
+    components: Vec<&'static str>,
+    location: Option<Location>,
+    depth: usize,
+}
+
+/// Records a derived template while it renders, to report the component stack
+/// if rendering panics.
+///
+/// Derived templates create a guard in debug builds. When a panic unwinds
+/// through the outermost template, the component stack is printed to stderr
+/// following the panic message.
+#[doc(hidden)]
+pub struct ComponentGuard<'a> {
+    name: &'static str,
+    location: &'a Cell<Option<Location>>,
+    depth: usize,
+}
+
+impl<'a> ComponentGuard<'a> {
+    pub fn enter(name: &'static str, location: &'a Cell<Option<Location>>) -> Self {
+        let depth = DEPTH.with(|depth| depth.replace(depth.get() + 1));
+
+        ComponentGuard {
+            name,
+            location,
+            depth,
+        }
+    }
+}
+
+impl Drop for ComponentGuard<'_> {
+    fn drop(&mut self) {
+        DEPTH.with(|depth| depth.set(self.depth));
+
+        if !std::thread::panicking() {
+            // A panic in a nested template was caught, forget about it
+            PANIC_STACK.with(|stack| stack.borrow_mut().take());
+            return;
+        }
+
+        let stack = PANIC_STACK.with(|stack| {
+            let mut stack = stack.borrow_mut();
+            match stack.as_mut() {
+                Some(stack) if stack.depth == self.depth + 1 => {
+                    stack.components.insert(0, self.name);
+                    stack.depth = self.depth;
+                }
+                _ => {
+                    *stack = Some(PanicStack {
+                        components: vec![self.name],
+                        location: self.location.get(),
+                        depth: self.depth,
+                    });
+                }
+            }
+
+            if self.depth == 0 {
+                stack.take()
+            } else {
+                None
+            }
+        });
+
+        if let Some(stack) = stack {
+            // Printing must not panic again while unwinding
+            let _ = writeln!(
+                std::io::stderr(),
+                "note: panicked while rendering {}",
+                ComponentStack {
+                    components: &stack.components,
+                    location: stack.location.as_ref(),
+                }
+            );
+        }
+    }
+}
+
+/// A location in a template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    /// The template file, or `None` for templates declared inline.
+    pub file: Option<&'static str>,
+    /// The line in the template, starting at 1.
+    pub line: u32,
+    /// The column in the line, starting at 1.
+    pub column: u32,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.file {
+            Some(file) => write!(f, "{}:{}:{}", file, self.line, self.column),
+            None => write!(f, "line {}, column {}", self.line, self.column),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::Cell, panic};
+
+    use super::{ComponentGuard, Location, RenderError, PANIC_STACK};
+
+    #[test]
+    fn component_stack() {
+        let error = RenderError::new("failed")
+            .in_component(
+                "UserCard",
+                Some(Location {
+                    file: Some("templates/user_card.html"),
+                    line: 12,
+                    column: 5,
+                }),
+            )
+            .in_component("Sidebar", None)
+            .in_component("Page", None);
+
+        assert_eq!(
+            "Page > Sidebar > UserCard (templates/user_card.html:12:5)",
+            error.component_stack().to_string()
+        );
+        assert_eq!(
+            "failed, in Page > Sidebar > UserCard (templates/user_card.html:12:5)",
+            error.to_string()
+        );
+        assert_eq!("failed", RenderError::new("failed").to_string());
+    }
+
+    #[test]
+    fn panic_stack() {
+        let outer_location = Cell::new(None);
+        let _outer = ComponentGuard::enter("Page", &outer_location);
+
+        let result = panic::catch_unwind(|| {
+            let middle_location = Cell::new(None);
+            let _middle = ComponentGuard::enter("Sidebar", &middle_location);
+
+            let inner_location = Cell::new(Some(Location {
+                file: None,
+                line: 2,
+                column: 3,
+            }));
+            let _inner = ComponentGuard::enter("UserCard", &inner_location);
+
+            panic!("rendering failed");
+        });
+        assert!(result.is_err());
+
+        PANIC_STACK.with(|stack| {
+            let stack = stack.borrow();
+            let stack = stack.as_ref().unwrap();
+
+            assert_eq!(vec!["Sidebar", "UserCard"], stack.components);
+            assert_eq!(Some(2), stack.location.map(|l| l.line));
+        });
+
+        // Rendering continues after the panic was caught
+        let location = Cell::new(None);
+        drop(ComponentGuard::enter("Footer", &location));
+
+        PANIC_STACK.with(|stack| assert!(stack.borrow().is_none()));
+    }
+}
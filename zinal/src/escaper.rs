@@ -1,7 +1,83 @@
-use std::borrow::Cow;
+use std::fmt;
+
+use crate::html::HtmlEscaper;
 
 /// Trait for objects that escape some content to be safely included in their context.
 pub trait Escaper {
-    /// Escape the given value to be safe to be included in the escapers context.
-    fn escape<'a>(&self, value: Cow<'a, str>) -> Cow<'a, str>;
+    /// Write the given value to the writer, escaped to be safe to be included
+    /// in the escapers context.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the writer returns an error when writing.
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result;
+
+    /// Write the given JSON text to the writer, escaped to be safe to be
+    /// included in the escapers context.
+    ///
+    /// By default the JSON is escaped like any other value. Escapers for
+    /// script content write it as a JavaScript value instead of a string.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the writer returns an error when writing.
+    fn escape_json(&self, json: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        self.escape(json, writer)
+    }
+}
+
+/// A writer that escapes everything written to it before passing it on.
+///
+/// Useful to render values implementing [fmt::Display] without formatting
+/// them into a string first.
+///
+/// # Example
+/// ```rust
+/// use std::fmt::Write;
+/// use zinal::{html::HtmlEscaper, EscapingWriter};
+///
+/// let mut output = String::new();
+/// write!(EscapingWriter::new(&HtmlEscaper, &mut output), "{} & {}", 1, "<2>").unwrap();
+///
+/// assert_eq!("1 &amp; &lt;2&gt;", output);
+/// ```
+pub struct EscapingWriter<'a> {
+    escaper: &'a dyn Escaper,
+    writer: &'a mut dyn fmt::Write,
+}
+
+impl<'a> EscapingWriter<'a> {
+    /// Create a writer escaping with `escaper` and writing to `writer`.
+    pub fn new(escaper: &'a dyn Escaper, writer: &'a mut dyn fmt::Write) -> Self {
+        Self { escaper, writer }
+    }
+}
+
+impl fmt::Write for EscapingWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.escaper.escape(s, self.writer)
+    }
+}
+
+/// An escaper that escapes strings to be safely included in XML content and attributes.
+///
+/// Used by templates declared with `escape = "xml"` or with a `.xml` file.
+pub struct XmlEscaper;
+
+impl Escaper for XmlEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        // XML has the same predefined entities as escaped by the HTML escaper
+        HtmlEscaper.escape(value, writer)
+    }
+}
+
+/// An escaper that leaves values unchanged, for plain text output.
+///
+/// Used by templates declared with `escape = "none"` or with a `.txt` or `.md` file.
+pub struct NoEscaper;
+
+impl Escaper for NoEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        writer.write_str(value)
+    }
 }
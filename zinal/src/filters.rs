@@ -0,0 +1,245 @@
+//! Built-in filters for template expressions.
+//!
+//! Filters are applied to the value of an expression with `|`, optionally
+//! passing further arguments:
+//!
+//! ```rust
+//! use zinal::*;
+//!
+//! #[derive(Template)]
+//! #[template("<h1>{{ self.title | trim | upper | truncate(10) }}</h1>")]
+//! struct Heading {
+//!   title: String,
+//! }
+//!
+//! let heading = Heading { title: String::from("  A tale of two cities ") };
+//!
+//! assert_eq!("<h1>A TALE OF ...</h1>".to_owned(), heading.render_to_string().unwrap());
+//! ```
+//!
+//! A filter is an ordinary function taking a reference to the value as its
+//! first parameter, followed by the arguments given in the template. The
+//! filters of this module that a template uses are imported where it is
+//! declared, and a function or import of the same name takes precedence over
+//! them. Custom filters are declared as functions where the template is
+//! declared and are written as calls:
+//!
+//! ```rust
+//! use zinal::*;
+//!
+//! fn shout(value: &str) -> String {
+//!   format!("{}!", value)
+//! }
+//!
+//! #[derive(Template)]
+//! #[template("<p>{{ self.greeting | shout() }}</p>")]
+//! struct Greeting {
+//!   greeting: String,
+//! }
+//!
+//! let greeting = Greeting { greeting: String::from("Hello") };
+//!
+//! assert_eq!("<p>Hello!</p>".to_owned(), greeting.render_to_string().unwrap());
+//! ```
+//!
+//! Only a call or the name of a filter of this module following `|` is taken
+//! as filter, so `{{ self.flags | MASK }}`, `{{ self.flags | 0x1 }}` and
+//! `{{ a | b }}` are bitwise or expressions.
+
+use std::{borrow::Cow, fmt};
+
+/// Convert the value to upper case.
+pub fn upper<T: fmt::Display + ?Sized>(value: &T) -> String {
+    value.to_string().to_uppercase()
+}
+
+/// Convert the value to lower case.
+pub fn lower<T: fmt::Display + ?Sized>(value: &T) -> String {
+    value.to_string().to_lowercase()
+}
+
+/// Remove leading and trailing whitespace from the value.
+pub fn trim<T: fmt::Display + ?Sized>(value: &T) -> String {
+    value.to_string().trim().to_owned()
+}
+
+/// Shorten the value to the given number of characters, appending `...` if
+/// it was shortened.
+pub fn truncate<T: fmt::Display + ?Sized>(value: &T, length: usize) -> String {
+    let value = value.to_string();
+
+    match value.char_indices().nth(length) {
+        Some((end, _)) => format!("{}...", &value[..end]),
+        None => value,
+    }
+}
+
+/// Join the items of the value with the given separator, see [join()](crate::join).
+pub use crate::join;
+
+/// Use the fallback if the value is empty, see [MaybeEmpty].
+pub fn default<T, D>(value: &T, fallback: D) -> String
+where
+    T: MaybeEmpty + ?Sized,
+    D: fmt::Display,
+{
+    value.present().unwrap_or_else(|| fallback.to_string())
+}
+
+/// Percent-encode the value to be used as part of an URL.
+///
+/// All characters except for ASCII letters, digits and `-`, `.`, `_` and `~` are encoded.
+pub fn urlencode<T: fmt::Display + ?Sized>(value: &T) -> String {
+    let value = value.to_string();
+    let mut encoded = String::with_capacity(value.len());
+
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            encoded.push_str(&format!("%{:02X}", byte));
+        }
+    }
+
+    encoded
+}
+
+/// Select the singular or plural form depending on the count given as value.
+///
+/// ```rust
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("{{ self.count }} {{ self.count | pluralize(\"item\", \"items\") }}")]
+/// struct Items {
+///   count: u32,
+/// }
+///
+/// assert_eq!("1 item".to_owned(), Items { count: 1 }.render_to_string().unwrap());
+/// assert_eq!("3 items".to_owned(), Items { count: 3 }.render_to_string().unwrap());
+/// ```
+pub fn pluralize<'a, T>(value: &T, singular: &'a str, plural: &'a str) -> &'a str
+where
+    T: PartialEq + From<u8>,
+{
+    if *value == T::from(1) {
+        singular
+    } else {
+        plural
+    }
+}
+
+/// Serialize the value as JSON.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub fn json<T: serde::Serialize + ?Sized>(value: &T) -> Json<'_, T> {
+    Json(value)
+}
+
+/// A value rendered as JSON, returned by the [json] filter.
+#[cfg(feature = "json")]
+#[cfg_attr(docsrs, doc(cfg(feature = "json")))]
+pub struct Json<'a, T: ?Sized>(&'a T);
+
+#[cfg(feature = "json")]
+impl<T: serde::Serialize + ?Sized> fmt::Display for Json<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let json = serde_json::to_string(self.0).map_err(|_| fmt::Error)?;
+        f.write_str(&json)
+    }
+}
+
+#[cfg(feature = "json")]
+impl<T: serde::Serialize + ?Sized> crate::Renderable for Json<'_, T> {
+    fn render(
+        &self,
+        writer: &mut dyn fmt::Write,
+        escaper: &dyn crate::Escaper,
+    ) -> Result<(), crate::RenderError> {
+        escaper.escape_json(&self.to_string(), writer)?;
+        Ok(())
+    }
+}
+
+/// The filters that are available in templates, imported where a template is
+/// declared so that functions of the same name take precedence.
+#[doc(hidden)]
+pub mod builtin {
+    #[cfg(feature = "json")]
+    pub use super::json;
+    pub use super::{default, join, lower, pluralize, trim, truncate, upper, urlencode};
+}
+
+/// Values that can be empty, used by the [default] filter.
+///
+/// `None` and empty strings are considered empty.
+pub trait MaybeEmpty {
+    /// Returns the value as string if it is not empty.
+    fn present(&self) -> Option<String>;
+}
+
+impl<T: fmt::Display> MaybeEmpty for Option<T> {
+    fn present(&self) -> Option<String> {
+        self.as_ref().map(ToString::to_string)
+    }
+}
+
+impl MaybeEmpty for str {
+    fn present(&self) -> Option<String> {
+        (!self.is_empty()).then(|| self.to_owned())
+    }
+}
+
+impl MaybeEmpty for String {
+    fn present(&self) -> Option<String> {
+        self.as_str().present()
+    }
+}
+
+impl MaybeEmpty for Cow<'_, str> {
+    fn present(&self) -> Option<String> {
+        self.as_ref().present()
+    }
+}
+
+impl<T: MaybeEmpty + ?Sized> MaybeEmpty for &T {
+    fn present(&self) -> Option<String> {
+        (**self).present()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{default, join, pluralize, truncate, urlencode};
+
+    #[test]
+    fn truncate_value() {
+        assert_eq!(truncate("Hello", 5), "Hello");
+        assert_eq!(truncate("Hello, World", 5), "Hello...");
+        assert_eq!(truncate("Grüße", 3), "Grü...");
+    }
+
+    #[test]
+    fn join_values() {
+        assert_eq!(join(&vec![1, 2, 3], ", ").to_string(), "1, 2, 3");
+        assert_eq!(join(&[] as &[u8], ", ").to_string(), "");
+    }
+
+    #[test]
+    fn default_value() {
+        assert_eq!(default(&Some("Mary"), "anonymous"), "Mary");
+        assert_eq!(default(&None::<&str>, "anonymous"), "anonymous");
+        assert_eq!(default("", "anonymous"), "anonymous");
+    }
+
+    #[test]
+    fn urlencode_value() {
+        assert_eq!(urlencode("a b&c=ü~"), "a%20b%26c%3D%C3%BC~");
+    }
+
+    #[test]
+    fn pluralize_value() {
+        assert_eq!(pluralize(&1u32, "item", "items"), "item");
+        assert_eq!(pluralize(&0u32, "item", "items"), "items");
+    }
+}
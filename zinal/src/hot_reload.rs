@@ -0,0 +1,119 @@
+//! Support for detecting changed template files at render time.
+//!
+//! This module is used by derived templates when the `hot-reload` feature is
+//! enabled. In debug builds, templates declared with `path = "..."` check their
+//! template files, and the files of the templates they extend, every time they
+//! are rendered, reading them again only if they were modified.
+//!
+//! Templates are not interpreted at render time, as that would need a second
+//! implementation of the template syntax next to the derive macro. Instead, if
+//! a file changed since the application was compiled, an error page is
+//! rendered saying which file changed and asking to rebuild the application,
+//! so outdated output is never mistaken for the edited template. Unchanged
+//! templates are rendered with the compiled code.
+//!
+//! Release builds always use the compiled code.
+
+use std::{
+    collections::BTreeMap,
+    fmt, fs, io,
+    sync::{Arc, Mutex, PoisonError},
+    time::SystemTime,
+};
+
+use crate::{html::HtmlEscaper, Escaper, RenderError};
+
+/// A template file together with the content it had at compile time.
+pub type Source = (&'static str, &'static str);
+
+/// Render an error page if any of the template files changed since compilation.
+///
+/// The first source is the template itself, followed by the templates it extends.
+/// Returns `Ok(false)` if none of the files changed, in which case the compiled
+/// code should be used to render the template.
+pub fn render(sources: &[Source], writer: &mut dyn fmt::Write) -> Result<bool, RenderError> {
+    for &(path, compiled) in sources {
+        let message = match read(path) {
+            Ok(content) if *content == *compiled => continue,
+            Ok(_) => String::from(
+                "The template changed since the application was compiled. \
+                 Rebuild the application to apply the changes.",
+            ),
+            Err(err) => format!("Could not read template: {}", err),
+        };
+
+        render_error_page(writer, path, &message)?;
+        return Ok(true);
+    }
+
+    Ok(false)
+}
+
+/// Read the template file, unless it was not modified since it was last read.
+fn read(path: &'static str) -> io::Result<Arc<str>> {
+    static FILES: Mutex<BTreeMap<&str, (SystemTime, Arc<str>)>> = Mutex::new(BTreeMap::new());
+
+    let modified = fs::metadata(path)?.modified()?;
+    let mut files = FILES.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Some((read, content)) = files.get(path) {
+        if *read == modified {
+            return Ok(content.clone());
+        }
+    }
+
+    let content = Arc::<str>::from(fs::read_to_string(path)?);
+    files.insert(path, (modified, content.clone()));
+
+    Ok(content)
+}
+
+fn render_error_page(writer: &mut dyn fmt::Write, path: &str, message: &str) -> fmt::Result {
+    let escaper = HtmlEscaper;
+
+    writer.write_str(
+        "<!DOCTYPE html>\
+         <html><head><title>Template error</title></head>\
+         <body><h1>Template error</h1><p>",
+    )?;
+    escaper.escape(path, writer)?;
+    writer.write_str("</p><pre>")?;
+    escaper.escape(message, writer)?;
+    writer.write_str("</pre></body></html>")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::render;
+
+    #[test]
+    fn render_changed_file() {
+        let path =
+            std::env::temp_dir().join(format!("zinal-hot-reload-{}.html", std::process::id()));
+        std::fs::write(&path, "<p>Hi {{self.name}}</p>").expect("Should write template");
+        let path: &'static str = Box::leak(path.to_string_lossy().into_owned().into_boxed_str());
+
+        let mut unchanged_output = String::new();
+        let mut changed_output = String::new();
+
+        let unchanged = render(&[(path, "<p>Hi {{self.name}}</p>")], &mut unchanged_output);
+        let changed = render(&[(path, "<p>Hello {{self.name}}</p>")], &mut changed_output);
+
+        std::fs::remove_file(path).expect("Should remove template");
+
+        assert!(!unchanged.unwrap());
+        assert!(unchanged_output.is_empty());
+        assert!(changed.unwrap());
+        assert!(changed_output.contains("Rebuild the application"));
+    }
+
+    #[test]
+    fn render_missing_file() {
+        let mut output = String::new();
+
+        let rendered = render(&[("templates/missing/file.html", "<p></p>")], &mut output);
+
+        assert!(rendered.unwrap());
+        assert!(output.contains("Could not read template"));
+    }
+}
@@ -1,53 +1,243 @@
 //! Utilities for rendering HTML.
 
-use std::{borrow::Cow, collections::HashMap, sync::OnceLock};
+use std::{cell::Cell, fmt};
 
-use crate::{Escaper, Renderable};
+use crate::{Escaper, RenderError, Renderable};
 
 /// An escaper that escapes strings to be safely included in HTML content.
 pub struct HtmlEscaper;
 
 impl Escaper for HtmlEscaper {
-    /// Escape the given value to be HTML safe.
+    /// Write the given value escaped to be HTML safe.
     ///
-    /// If the value is already safe, it is returned unchanged.
-    fn escape<'a>(&self, value: Cow<'a, str>) -> Cow<'a, str> {
-        let mut escaped = String::new();
-        let escapes = ESCAPES.get_or_init(|| {
-            HashMap::from([
-                ('<', "&lt;"),
-                ('>', "&gt;"),
-                ('&', "&amp;"),
-                ('\'', "&apos;"),
-                ('"', "&quot;"),
-            ])
-        });
-
+    /// Unescaped runs of the value are written as they are, so values without
+    /// any special characters are written with a single call to the writer.
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
         let mut previous_offset = 0;
-        let mut offset = 0;
 
-        for c in value.chars() {
-            if let Some(replacement) = escapes.get(&c) {
-                escaped.push_str(&value[previous_offset..offset]);
-                escaped.push_str(replacement);
+        // All escaped characters are ASCII, so they can't be part of multi-byte characters
+        for (offset, byte) in value.bytes().enumerate() {
+            let replacement = match byte {
+                b'<' => "&lt;",
+                b'>' => "&gt;",
+                b'&' => "&amp;",
+                b'\'' => "&apos;",
+                b'"' => "&quot;",
+                _ => continue,
+            };
 
-                offset += c.len_utf8();
-                previous_offset = offset;
-            } else {
-                offset += c.len_utf8();
-            }
+            writer.write_str(&value[previous_offset..offset])?;
+            writer.write_str(replacement)?;
+            previous_offset = offset + 1;
+        }
+
+        writer.write_str(&value[previous_offset..])
+    }
+}
+
+/// An escaper for unquoted attribute values, like `<input value={{ value }}>`.
+///
+/// All ASCII characters except letters and digits are replaced with character references.
+pub struct UnquotedAttributeEscaper;
+
+impl Escaper for UnquotedAttributeEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        escape_with(
+            value,
+            writer,
+            |c| c.is_ascii() && !c.is_ascii_alphanumeric(),
+            |c, writer| write!(writer, "&#x{:X};", c as u32),
+        )
+    }
+}
+
+/// An escaper for values in scripts outside of string literals, like `<script>let name = {{ name }};</script>`.
+///
+/// The value is rendered as a JavaScript string literal, escaped like with [ScriptStringEscaper].
+/// Numbers and booleans are not escaped and are rendered as they are, and JSON from the `json`
+/// filter is rendered as JavaScript value.
+///
+/// The string literal is opened when the value is first escaped, so values escaping several parts,
+/// like [join()](crate::join), render a single string. Use a new escaper for every value and call
+/// [ScriptEscaper::finish()] after rendering it to close the string literal.
+#[derive(Debug, Default)]
+pub struct ScriptEscaper {
+    quoted: Cell<bool>,
+}
+
+impl ScriptEscaper {
+    /// Create a new escaper for a single value.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Close the string literal if one was opened.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the writer returns an error when writing.
+    pub fn finish(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        if self.quoted.replace(false) {
+            writer.write_char('"')?;
         }
 
-        if !escaped.is_empty() {
-            escaped.push_str(&value[previous_offset..offset]);
-            escaped.into()
-        } else {
-            value
+        Ok(())
+    }
+}
+
+impl Escaper for ScriptEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        if !self.quoted.replace(true) {
+            writer.write_char('"')?;
         }
+
+        ScriptStringEscaper.escape(value, writer)
+    }
+
+    fn escape_json(&self, json: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        if self.quoted.get() {
+            return self.escape(json, writer);
+        }
+
+        // These characters can only occur in JSON strings, where escape sequences are valid
+        escape_with(
+            json,
+            writer,
+            |c| matches!(c, '<' | '>' | '&' | '/' | '\u{2028}' | '\u{2029}'),
+            |c, writer| write!(writer, "\\u{:04X}", c as u32),
+        )
+    }
+}
+
+/// An escaper for values in JavaScript string literals, like `<script>alert("{{ message }}")</script>`.
+///
+/// Quotes, backslashes and characters that could end the script element are
+/// replaced with escape sequences.
+pub struct ScriptStringEscaper;
+
+impl Escaper for ScriptStringEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        escape_with(
+            value,
+            writer,
+            |c| c.is_control() || "\\\"'`<>&/$\u{2028}\u{2029}".contains(c),
+            |c, writer| match c {
+                '\\' => writer.write_str("\\\\"),
+                '\n' => writer.write_str("\\n"),
+                '\r' => writer.write_str("\\r"),
+                '\t' => writer.write_str("\\t"),
+                c => write!(writer, "\\u{:04X}", c as u32),
+            },
+        )
+    }
+}
+
+/// An escaper for values in style elements, like `<style>p { color: {{ color }} }</style>`.
+///
+/// All ASCII characters except letters, digits, spaces and `#`, `%`, `,`, `-`, `.` and `_`
+/// are replaced with CSS escape sequences.
+pub struct StyleEscaper;
+
+impl Escaper for StyleEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        escape_with(
+            value,
+            writer,
+            |c| {
+                let safe = c.is_ascii_alphanumeric()
+                    || matches!(c, ' ' | '#' | '%' | ',' | '-' | '.' | '_');
+                c.is_ascii() && !safe
+            },
+            |c, writer| write!(writer, "\\{:X} ", c as u32),
+        )
+    }
+}
+
+/// An escaper for values at the start of URL attributes, like `<a href="{{ url }}">`.
+///
+/// URLs with a `javascript:` or `vbscript:` scheme are replaced with `about:invalid`,
+/// everything else is escaped like with [UrlPartEscaper]. The scheme is checked on every
+/// escaped value, so derived templates render values in URL attributes into a string first
+/// and escape it as a whole.
+pub struct UrlEscaper;
+
+impl Escaper for UrlEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        if has_unsafe_scheme(value) {
+            return writer.write_str("about:invalid");
+        }
+
+        UrlPartEscaper.escape(value, writer)
     }
 }
 
-static ESCAPES: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
+/// An escaper for values in URL attributes after the start of the URL, like `<a href="/users/{{ id }}">`.
+///
+/// Characters that are not allowed in URLs are percent-encoded, while
+/// characters with a special meaning in URLs (like `/`, `?` or `%`) are kept.
+/// Use the `urlencode` filter to encode those as well.
+pub struct UrlPartEscaper;
+
+impl Escaper for UrlPartEscaper {
+    fn escape(&self, value: &str, writer: &mut dyn fmt::Write) -> fmt::Result {
+        escape_with(
+            value,
+            writer,
+            |c| !(c.is_ascii_alphanumeric() || "-._~:/?#[]@!$()*+,;=%".contains(c)),
+            |c, writer| {
+                if c == '&' {
+                    return writer.write_str("&amp;");
+                }
+
+                let mut buffer = [0; 4];
+                for byte in c.encode_utf8(&mut buffer).bytes() {
+                    write!(writer, "%{:02X}", byte)?;
+                }
+
+                Ok(())
+            },
+        )
+    }
+}
+
+/// Checks whether the URL uses a scheme executing code. Browsers ignore
+/// leading whitespace and control characters as well as tabs and newlines
+/// in the scheme, so these are ignored as well.
+fn has_unsafe_scheme(url: &str) -> bool {
+    let Some((scheme, _)) = url.split_once(':') else {
+        return false;
+    };
+
+    let scheme = scheme
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_ascii_control())
+        .map(|c| c.to_ascii_lowercase());
+
+    ["javascript", "vbscript"]
+        .into_iter()
+        .any(|unsafe_scheme| scheme.clone().eq(unsafe_scheme.chars()))
+}
+
+/// Write the value, writing every character for which `is_escaped` returns
+/// true with `escape` instead.
+fn escape_with(
+    value: &str,
+    writer: &mut dyn fmt::Write,
+    is_escaped: impl Fn(char) -> bool,
+    escape: impl Fn(char, &mut dyn fmt::Write) -> fmt::Result,
+) -> fmt::Result {
+    let mut previous_offset = 0;
+
+    for (offset, c) in value.char_indices() {
+        if is_escaped(c) {
+            writer.write_str(&value[previous_offset..offset])?;
+            escape(c, writer)?;
+            previous_offset = offset + c.len_utf8();
+        }
+    }
+
+    writer.write_str(&value[previous_offset..])
+}
 
 /// Render an attribute with the given value.
 ///
@@ -64,8 +254,8 @@ static ESCAPES: OnceLock<HashMap<char, &'static str>> = OnceLock::new();
 /// let input1 = Input { read_only: true };
 /// let input2 = Input { read_only: false };
 ///
-/// assert_eq!(Ok("<input readonly>".to_owned()), input1.render_to_string());
-/// assert_eq!(Ok("<input >".to_owned()), input2.render_to_string());
+/// assert_eq!("<input readonly>".to_owned(), input1.render_to_string().unwrap());
+/// assert_eq!("<input >".to_owned(), input2.render_to_string().unwrap());
 /// ```
 pub fn attr<T: AttributeValue>(name: &str, value: T) -> Attribute<'_, T> {
     Attribute { name, value }
@@ -84,7 +274,7 @@ impl<'a, T: AttributeValue> Renderable for Attribute<'a, T> {
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
+    ) -> Result<(), RenderError> {
         self.value.render_attribute(self.name, writer, escaper)
     }
 }
@@ -103,7 +293,7 @@ pub trait AttributeValue {
         name: &str,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error>;
+    ) -> Result<(), RenderError>;
 }
 
 impl AttributeValue for &str {
@@ -112,13 +302,11 @@ impl AttributeValue for &str {
         name: &str,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
-        write!(
-            writer,
-            "{}=\"{}\"",
-            name,
-            escaper.escape(Cow::Borrowed(self))
-        )
+    ) -> Result<(), RenderError> {
+        write!(writer, "{}=\"", name)?;
+        escaper.escape(self, writer)?;
+        writer.write_char('"')?;
+        Ok(())
     }
 }
 
@@ -128,13 +316,11 @@ impl AttributeValue for String {
         name: &str,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
-        write!(
-            writer,
-            "{}=\"{}\"",
-            name,
-            escaper.escape(Cow::Borrowed(self))
-        )
+    ) -> Result<(), RenderError> {
+        write!(writer, "{}=\"", name)?;
+        escaper.escape(self, writer)?;
+        writer.write_char('"')?;
+        Ok(())
     }
 }
 
@@ -144,11 +330,12 @@ impl AttributeValue for bool {
         name: &str,
         writer: &mut dyn std::fmt::Write,
         _escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
+    ) -> Result<(), RenderError> {
         if !(*self) {
             return Ok(());
         }
-        write!(writer, "{}", name,)
+        write!(writer, "{}", name,)?;
+        Ok(())
     }
 }
 
@@ -161,7 +348,7 @@ where
         name: &str,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
+    ) -> Result<(), RenderError> {
         match self {
             Some(value) => value.render_attribute(name, writer, escaper),
             None => Ok(()),
@@ -171,47 +358,110 @@ where
 
 #[cfg(test)]
 mod tests {
-    use std::borrow::Cow;
+    use std::fmt;
+
+    use crate::Escaper;
 
-    use crate::Escaper as _;
+    use super::{
+        HtmlEscaper, ScriptEscaper, ScriptStringEscaper, StyleEscaper, UnquotedAttributeEscaper,
+        UrlEscaper, UrlPartEscaper,
+    };
 
-    use super::HtmlEscaper;
+    fn escape(escaper: &dyn Escaper, value: &str) -> String {
+        let mut output = String::new();
+        escaper.escape(value, &mut output).unwrap();
+        output
+    }
 
     #[test]
     fn escaper_unescaped() {
-        let input = Cow::from("This does not need to be escaped");
+        struct CountingWriter(Vec<String>);
+
+        impl fmt::Write for CountingWriter {
+            fn write_str(&mut self, s: &str) -> fmt::Result {
+                self.0.push(s.to_owned());
+                Ok(())
+            }
+        }
+
+        let mut writer = CountingWriter(Vec::new());
 
-        let output = HtmlEscaper.escape(input);
+        HtmlEscaper
+            .escape("This does not need to be escaped", &mut writer)
+            .unwrap();
+
+        assert_eq!(writer.0, vec!["This does not need to be escaped"]);
+    }
+
+    #[test]
+    fn escaper_escaped() {
+        let output = escape(&HtmlEscaper, "<&'\">");
+
+        assert_eq!(output, "&lt;&amp;&apos;&quot;&gt;");
+    }
+
+    #[test]
+    fn escaper_mixed() {
+        let output = escape(&HtmlEscaper, "< 'hello' & \"world\" >");
 
         assert_eq!(
             output,
-            Cow::<str>::Borrowed("This does not need to be escaped")
+            "&lt; &apos;hello&apos; &amp; &quot;world&quot; &gt;"
         );
     }
 
     #[test]
-    fn escaper_escaped() {
-        let input = Cow::from("<&'\">");
+    fn unquoted_attribute_escaper() {
+        let output = escape(&UnquotedAttributeEscaper, "a b=\"c\">ü");
 
-        let output = HtmlEscaper.escape(input);
+        assert_eq!(output, "a&#x20;b&#x3D;&#x22;c&#x22;&#x3E;ü");
+    }
+
+    #[test]
+    fn script_escapers() {
+        let input = "</script>\"it's\"\n\\";
 
         assert_eq!(
-            output,
-            Cow::<str>::Owned(String::from("&lt;&amp;&apos;&quot;&gt;"))
+            escape(&ScriptStringEscaper, input),
+            "\\u003C\\u002Fscript\\u003E\\u0022it\\u0027s\\u0022\\n\\\\"
         );
+
+        let script = ScriptEscaper::new();
+        let mut output = String::new();
+        script.escape("a'b", &mut output).unwrap();
+        script.escape(", c", &mut output).unwrap();
+        script.finish(&mut output).unwrap();
+        script.escape_json("[\"</script>\"]", &mut output).unwrap();
+        script.finish(&mut output).unwrap();
+
+        assert_eq!(output, "\"a\\u0027b, c\"[\"\\u003C\\u002Fscript\\u003E\"]");
     }
 
     #[test]
-    fn escaper_mixed() {
-        let input = Cow::from("< 'hello' & \"world\" >");
+    fn style_escaper() {
+        let output = escape(&StyleEscaper, "red; } body { x: url(a)");
 
-        let output = HtmlEscaper.escape(input);
+        assert_eq!(output, "red\\3B  \\7D  body \\7B  x\\3A  url\\28 a\\29 ");
+    }
 
+    #[test]
+    fn url_escapers() {
         assert_eq!(
-            output,
-            Cow::<str>::Owned(String::from(
-                "&lt; &apos;hello&apos; &amp; &quot;world&quot; &gt;"
-            ))
+            escape(&UrlEscaper, "/search?q=a b&c=\"ü\""),
+            "/search?q=a%20b&amp;c=%22%C3%BC%22"
+        );
+        assert_eq!(
+            escape(&UrlEscaper, " Java\tScript:alert(1)"),
+            "about:invalid"
+        );
+        assert_eq!(escape(&UrlEscaper, "vbscript:x"), "about:invalid");
+        assert_eq!(
+            escape(&UrlPartEscaper, "javascript:alert(1)"),
+            "javascript:alert(1)"
+        );
+        assert_eq!(
+            escape(&UrlEscaper, "https://example.com/?a=javascript:"),
+            "https://example.com/?a=javascript:"
         );
     }
 }
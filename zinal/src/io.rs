@@ -0,0 +1,85 @@
+use std::{
+    fmt,
+    io::{self, BufWriter, Write as _},
+};
+
+use crate::RenderError;
+
+/// Adapter to render templates into an [io::Write], like a file or a socket.
+///
+/// Output is buffered before it is written to the underlying writer. If
+/// writing fails, the original [io::Error] is kept and returned by
+/// [IoWriter::finish()], as the writer itself can only report a [fmt::Error].
+///
+/// # Example
+/// ```rust
+/// use zinal::*;
+///
+/// #[derive(Template)]
+/// #[template("<p>{{ self.text }}</p>")]
+/// struct Paragraph<'a> {
+///   text: &'a str,
+/// }
+///
+/// let mut output = Vec::new();
+/// let mut writer = IoWriter::new(&mut output);
+///
+/// let result = Paragraph { text: "Hello" }.render(
+///   &mut writer,
+///   &html::HtmlEscaper,
+///   &Context::new(),
+///   EmptyChildren,
+/// );
+/// writer.finish(result).unwrap();
+///
+/// assert_eq!(b"<p>Hello</p>", output.as_slice());
+/// ```
+pub struct IoWriter<W: io::Write> {
+    inner: BufWriter<W>,
+    error: Option<io::Error>,
+}
+
+impl<W: io::Write> IoWriter<W> {
+    /// Create a new adapter writing to the given writer.
+    pub fn new(writer: W) -> Self {
+        IoWriter {
+            inner: BufWriter::new(writer),
+            error: None,
+        }
+    }
+
+    /// Flush the buffered output and return the outcome of rendering.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the underlying writer if writing or flushing
+    /// failed, or the render error if rendering failed otherwise.
+    pub fn finish(mut self, result: Result<(), RenderError>) -> io::Result<()> {
+        if let Some(error) = self.error.take() {
+            return Err(error);
+        }
+
+        result?;
+
+        self.inner.flush()
+    }
+}
+
+impl<W: io::Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.inner.write_all(s.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+/// Writes rendered output directly into a byte vector.
+pub(crate) struct VecWriter<'a>(pub(crate) &'a mut Vec<u8>);
+
+impl fmt::Write for VecWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
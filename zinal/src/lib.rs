@@ -43,18 +43,33 @@
 #![deny(unsafe_code)]
 #![warn(missing_docs)]
 
+mod adapters;
 mod children;
 mod context;
+mod error;
 mod escaper;
+mod io;
 mod renderable;
 mod template;
 
 pub mod builder;
+pub mod filters;
 pub mod html;
 
+#[cfg(feature = "hot-reload")]
+#[cfg_attr(docsrs, doc(cfg(feature = "hot-reload")))]
+pub mod hot_reload;
+
+#[cfg(feature = "stream")]
+#[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+pub mod stream;
+
+pub use adapters::*;
 pub use children::*;
 pub use context::*;
+pub use error::*;
 pub use escaper::*;
+pub use io::*;
 pub use renderable::*;
 pub use template::*;
 
@@ -1,7 +1,12 @@
 use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::num::{
+    NonZeroI128, NonZeroI16, NonZeroI32, NonZeroI64, NonZeroI8, NonZeroIsize, NonZeroU128,
+    NonZeroU16, NonZeroU32, NonZeroU64, NonZeroU8, NonZeroUsize,
+};
 use std::ops::Deref;
 
-use crate::Escaper;
+use crate::{Escaper, EscapingWriter, RenderError};
 
 /// Implemented by values that can be rendered to a template.
 pub trait Renderable {
@@ -11,10 +16,11 @@ pub trait Renderable {
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error>;
+    ) -> Result<(), RenderError>;
 }
 
-// TODO: Enable this once impl specialization is available on stable Rust
+// TODO: Enable this once impl specialization is available on stable Rust.
+// Until then, types implementing Display can be rendered with the Display adapter.
 
 // impl<T> Renderable for T
 // where
@@ -24,11 +30,9 @@ pub trait Renderable {
 //         &self,
 //         writer: &mut dyn std::fmt::Write,
 //         escaper: &dyn Escaper,
-//     ) -> Result<(), std::fmt::Error> {
+//     ) -> Result<(), RenderError> {
 //         let raw = format!("{}", self);
-//         let escaped = escaper.escape(Cow::Owned(raw));
-
-//         write!(writer, "{}", escaped)
+//         escaper.escape(&raw, writer)
 //     }
 // }
 
@@ -39,8 +43,9 @@ macro_rules! render_unescaped {
                 &self,
                 writer: &mut dyn std::fmt::Write,
                 _: &dyn Escaper,
-            ) -> Result<(), std::fmt::Error> {
-                write!(writer, "{}", self)
+            ) -> Result<(), RenderError> {
+                write!(writer, "{}", self)?;
+                Ok(())
             }
         }
     };
@@ -48,21 +53,44 @@ macro_rules! render_unescaped {
 
 macro_rules! render_deref {
     ($t:ty) => {
+        render_deref!($t, T: Renderable + ?Sized);
+    };
+    ($t:ty, $($bounds:tt)+) => {
         impl<T> Renderable for $t
         where
-            T: Renderable,
+            $($bounds)+
         {
             fn render(
                 &self,
                 writer: &mut dyn std::fmt::Write,
                 escaper: &dyn Escaper,
-            ) -> Result<(), std::fmt::Error> {
+            ) -> Result<(), RenderError> {
                 Renderable::render(self.deref(), writer, escaper)
             }
         }
     };
 }
 
+macro_rules! render_tuple {
+    ($($name:ident),+) => {
+        impl<$($name),+> Renderable for ($($name,)+)
+        where
+            $($name: Renderable),+
+        {
+            fn render(
+                &self,
+                writer: &mut dyn std::fmt::Write,
+                escaper: &dyn Escaper,
+            ) -> Result<(), RenderError> {
+                #[allow(non_snake_case)]
+                let ($($name,)+) = self;
+                $(Renderable::render($name, writer, escaper)?;)+
+                Ok(())
+            }
+        }
+    };
+}
+
 render_unescaped!(bool);
 
 render_unescaped!(u8);
@@ -70,34 +98,60 @@ render_unescaped!(u16);
 render_unescaped!(u32);
 render_unescaped!(u64);
 render_unescaped!(u128);
-render_unescaped!(&u8);
-render_unescaped!(&u16);
-render_unescaped!(&u32);
-render_unescaped!(&u64);
-render_unescaped!(&u128);
+render_unescaped!(usize);
 
 render_unescaped!(i8);
 render_unescaped!(i16);
 render_unescaped!(i32);
 render_unescaped!(i64);
 render_unescaped!(i128);
-render_unescaped!(&i8);
-render_unescaped!(&i16);
-render_unescaped!(&i32);
-render_unescaped!(&i64);
-render_unescaped!(&i128);
+render_unescaped!(isize);
+
+render_unescaped!(NonZeroU8);
+render_unescaped!(NonZeroU16);
+render_unescaped!(NonZeroU32);
+render_unescaped!(NonZeroU64);
+render_unescaped!(NonZeroU128);
+render_unescaped!(NonZeroUsize);
+
+render_unescaped!(NonZeroI8);
+render_unescaped!(NonZeroI16);
+render_unescaped!(NonZeroI32);
+render_unescaped!(NonZeroI64);
+render_unescaped!(NonZeroI128);
+render_unescaped!(NonZeroIsize);
 
+render_unescaped!(f32);
+render_unescaped!(f64);
+
+render_deref!(&T);
+render_deref!(&mut T);
 render_deref!(std::boxed::Box<T>);
 render_deref!(std::rc::Rc<T>);
 render_deref!(std::sync::Arc<T>);
+render_deref!(Vec<T>, [T]: Renderable);
+
+render_tuple!(A);
+render_tuple!(A, B);
+render_tuple!(A, B, C);
+render_tuple!(A, B, C, D);
+render_tuple!(A, B, C, D, E);
+render_tuple!(A, B, C, D, E, F);
+render_tuple!(A, B, C, D, E, F, G);
+render_tuple!(A, B, C, D, E, F, G, H);
+render_tuple!(A, B, C, D, E, F, G, H, I);
+render_tuple!(A, B, C, D, E, F, G, H, I, J);
+render_tuple!(A, B, C, D, E, F, G, H, I, J, K);
+render_tuple!(A, B, C, D, E, F, G, H, I, J, K, L);
 
-impl Renderable for &str {
+impl Renderable for str {
     fn render(
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
-        write!(writer, "{}", escaper.escape(Cow::Borrowed(self)))
+    ) -> Result<(), RenderError> {
+        escaper.escape(self, writer)?;
+        Ok(())
     }
 }
 
@@ -106,8 +160,9 @@ impl Renderable for String {
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
-        write!(writer, "{}", escaper.escape(Cow::Borrowed(self)))
+    ) -> Result<(), RenderError> {
+        escaper.escape(self, writer)?;
+        Ok(())
     }
 }
 
@@ -116,8 +171,48 @@ impl<'a> Renderable for Cow<'a, str> {
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
-        write!(writer, "{}", escaper.escape(Cow::Borrowed(self)))
+    ) -> Result<(), RenderError> {
+        escaper.escape(self, writer)?;
+        Ok(())
+    }
+}
+
+/// Slices render all of their elements, one after another.
+impl<T: Renderable> Renderable for [T] {
+    fn render(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        for element in self {
+            Renderable::render(element, writer, escaper)?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: Renderable, const N: usize> Renderable for [T; N] {
+    fn render(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        Renderable::render(self.as_slice(), writer, escaper)
+    }
+}
+
+impl Renderable for char {
+    fn render(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        let mut buffer = [0; 4];
+        let value = self.encode_utf8(&mut buffer);
+
+        escaper.escape(value, writer)?;
+
+        Ok(())
     }
 }
 
@@ -129,10 +224,23 @@ where
         &self,
         writer: &mut dyn std::fmt::Write,
         escaper: &dyn Escaper,
-    ) -> Result<(), std::fmt::Error> {
+    ) -> Result<(), RenderError> {
         match self {
             Some(r) => Renderable::render(r, writer, escaper),
             None => Ok(()),
         }
     }
 }
+
+/// Formatted values, as created by [format_args], are escaped while they are
+/// formatted, without formatting them into a string first.
+impl Renderable for std::fmt::Arguments<'_> {
+    fn render(
+        &self,
+        writer: &mut dyn std::fmt::Write,
+        escaper: &dyn Escaper,
+    ) -> Result<(), RenderError> {
+        write!(EscapingWriter::new(escaper, writer), "{}", self)?;
+        Ok(())
+    }
+}
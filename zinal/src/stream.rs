@@ -0,0 +1,329 @@
+//! Support for rendering templates as a stream of chunks.
+//!
+//! Rendering large templates can take a while. Instead of waiting for the
+//! whole output, [Template::render_to_stream()] renders the template on a
+//! separate thread and yields the output in chunks as soon as they are
+//! available. Chunks are sent whenever the buffered output reaches the buffer
+//! size and before a child template is rendered, so content preceding slow
+//! components, like the `<head>` of a page, reaches the client early.
+//!
+//! Rendering pauses while the consumer is not keeping up with the output, and
+//! stops when the stream is dropped. If rendering fails, the stream ends with
+//! the error.
+//!
+//! Each stream renders on its own thread, which blocks while the consumer is
+//! not keeping up. By default a new OS thread is spawned for every stream, so
+//! servers rendering many streams at once should pass their own spawner, like
+//! a bounded thread pool, to [RenderStream::with_spawner()].
+//!
+//! With the `http-body` feature enabled, a [RenderStream] implements
+//! `http_body::Body`, so it can be used as streaming response body, for
+//! example with `axum::body::Body::new(stream)`.
+//!
+//! # Example
+//! ```rust
+//! use zinal::{stream::RenderStream, Template};
+//!
+//! #[derive(Template)]
+//! #[template("<ul><#for i in 0..self.count#><Item index={{i}} /><#end#></ul>")]
+//! struct Report {
+//!   count: usize,
+//! }
+//!
+//! #[derive(Template)]
+//! #[template("<li>{{self.index}}</li>")]
+//! struct Item {
+//!   index: usize,
+//! }
+//!
+//! let stream: RenderStream = Report { count: 1000 }.render_to_stream();
+//! ```
+
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt,
+    pin::Pin,
+    rc::Rc,
+    sync::{Arc, Condvar, Mutex, MutexGuard},
+    task::{Context as TaskContext, Poll, Waker},
+};
+
+use bytes::{Bytes, BytesMut};
+use futures_core::Stream;
+
+use crate::{html::HtmlEscaper, Context, EmptyChildren, RenderError, Template};
+
+/// The buffer size used by [Template::render_to_stream()].
+pub const DEFAULT_BUFFER_SIZE: usize = 8 * 1024;
+
+/// The number of chunks that are rendered ahead of the consumer.
+const MAX_PENDING_CHUNKS: usize = 4;
+
+/// A stream of rendered chunks of a template.
+///
+/// If rendering fails, the last item of the stream is the error.
+/// [RenderStream::failed()] returns whether the output is incomplete after the
+/// stream ended.
+pub struct RenderStream {
+    shared: Arc<Shared>,
+}
+
+impl RenderStream {
+    /// Start rendering the template, sending chunks of at least `buffer_size`
+    /// bytes unless a component boundary is reached first.
+    ///
+    /// Every stream spawns its own OS thread to render the template, which
+    /// ends when rendering is done or the stream is dropped. Use
+    /// [RenderStream::with_spawner()] to limit the number of threads.
+    pub fn new<T>(template: T, buffer_size: usize) -> Self
+    where
+        T: Template + Send + 'static,
+    {
+        Self::with_spawner(template, buffer_size, |render| {
+            std::thread::spawn(render);
+        })
+    }
+
+    /// Start rendering the template like [RenderStream::new()], running the
+    /// rendering job with the given spawner instead of a new OS thread.
+    ///
+    /// The job blocks while the consumer is not keeping up with the output,
+    /// so it should run where blocking is allowed, for example on a bounded
+    /// thread pool or with `tokio::task::spawn_blocking`.
+    ///
+    /// # Example
+    /// ```rust
+    /// use zinal::{stream::RenderStream, Template};
+    ///
+    /// #[derive(Template)]
+    /// #[template("<p>{{self.text}}</p>")]
+    /// struct Paragraph {
+    ///   text: String,
+    /// }
+    ///
+    /// let template = Paragraph { text: String::from("Hello") };
+    /// let stream = RenderStream::with_spawner(template, 1024, |render| {
+    ///   std::thread::Builder::new()
+    ///     .name(String::from("render"))
+    ///     .spawn(render)
+    ///     .unwrap();
+    /// });
+    /// ```
+    pub fn with_spawner<T, S>(template: T, buffer_size: usize, spawn: S) -> Self
+    where
+        T: Template + Send + 'static,
+        S: FnOnce(Box<dyn FnOnce() + Send>),
+    {
+        let shared = Arc::new(Shared::default());
+
+        let sender = Sender {
+            shared: Arc::clone(&shared),
+        };
+
+        spawn(Box::new(move || {
+            let sink = Rc::new(Sink {
+                buffer: RefCell::new(BytesMut::with_capacity(buffer_size)),
+                buffer_size,
+                sender,
+            });
+
+            let mut context = Context::new();
+            context.provide_param(Flush(Rc::clone(&sink)));
+
+            let result = template
+                .render(
+                    &mut SinkWriter(&sink),
+                    &HtmlEscaper,
+                    &context,
+                    EmptyChildren,
+                )
+                .and_then(|_| Ok(sink.flush()?));
+
+            sink.sender.finish(result.err());
+        }));
+
+        RenderStream { shared }
+    }
+
+    /// Returns whether rendering failed, meaning the output is incomplete.
+    pub fn failed(&self) -> bool {
+        self.shared.lock().failed
+    }
+
+    fn poll_chunk(&self, cx: &mut TaskContext<'_>) -> Poll<Option<Result<Bytes, RenderError>>> {
+        let mut state = self.shared.lock();
+
+        if let Some(chunk) = state.chunks.pop_front() {
+            self.shared.space.notify_one();
+            return Poll::Ready(Some(Ok(chunk)));
+        }
+
+        if state.finished {
+            return Poll::Ready(state.error.take().map(Err));
+        }
+
+        state.waker = Some(cx.waker().clone());
+        Poll::Pending
+    }
+}
+
+impl Stream for RenderStream {
+    type Item = Result<Bytes, RenderError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut TaskContext<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_chunk(cx)
+    }
+}
+
+impl Drop for RenderStream {
+    fn drop(&mut self) {
+        self.shared.lock().closed = true;
+        self.shared.space.notify_all();
+    }
+}
+
+/// Rendering errors end the body with an error, so that incomplete output is
+/// not taken for a complete response.
+#[cfg(feature = "http-body")]
+#[cfg_attr(docsrs, doc(cfg(feature = "http-body")))]
+impl http_body::Body for RenderStream {
+    type Data = Bytes;
+    type Error = std::io::Error;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut TaskContext<'_>,
+    ) -> Poll<Option<Result<http_body::Frame<Bytes>, Self::Error>>> {
+        self.poll_chunk(cx).map(|chunk| {
+            chunk.map(|chunk| {
+                chunk
+                    .map(http_body::Frame::data)
+                    .map_err(std::io::Error::from)
+            })
+        })
+    }
+}
+
+#[derive(Default)]
+struct Shared {
+    state: Mutex<State>,
+    /// Notified when the consumer took a chunk or dropped the stream.
+    space: Condvar,
+}
+
+impl Shared {
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[derive(Default)]
+struct State {
+    chunks: VecDeque<Bytes>,
+    waker: Option<Waker>,
+    finished: bool,
+    failed: bool,
+    error: Option<RenderError>,
+    closed: bool,
+}
+
+/// The sending half of the stream, owned by the rendering thread.
+struct Sender {
+    shared: Arc<Shared>,
+}
+
+impl Sender {
+    fn send(&self, chunk: Bytes) -> fmt::Result {
+        let mut state = self.shared.lock();
+
+        while state.chunks.len() >= MAX_PENDING_CHUNKS && !state.closed {
+            state = self
+                .shared
+                .space
+                .wait(state)
+                .unwrap_or_else(|e| e.into_inner());
+        }
+
+        if state.closed {
+            // Nobody is interested in the output anymore
+            return Err(fmt::Error);
+        }
+
+        state.chunks.push_back(chunk);
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+
+        Ok(())
+    }
+
+    fn finish(&self, error: Option<RenderError>) {
+        let mut state = self.shared.lock();
+        if state.finished {
+            return;
+        }
+
+        state.finished = true;
+        state.failed = error.is_some();
+        state.error = error;
+        if let Some(waker) = state.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+impl Drop for Sender {
+    /// Ends the stream if the rendering thread panicked.
+    fn drop(&mut self) {
+        self.finish(Some(RenderError::new("rendering panicked")));
+    }
+}
+
+/// Buffers output of the rendering thread until a chunk is complete.
+struct Sink {
+    buffer: RefCell<BytesMut>,
+    buffer_size: usize,
+    sender: Sender,
+}
+
+impl Sink {
+    fn write(&self, s: &str) -> fmt::Result {
+        let mut buffer = self.buffer.borrow_mut();
+        buffer.extend_from_slice(s.as_bytes());
+
+        if buffer.len() >= self.buffer_size {
+            let chunk = buffer.split().freeze();
+            drop(buffer);
+            self.sender.send(chunk)?;
+        }
+
+        Ok(())
+    }
+
+    fn flush(&self) -> fmt::Result {
+        let chunk = self.buffer.borrow_mut().split().freeze();
+        if chunk.is_empty() {
+            return Ok(());
+        }
+
+        self.sender.send(chunk)
+    }
+}
+
+struct SinkWriter<'a>(&'a Sink);
+
+impl fmt::Write for SinkWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0.write(s)
+    }
+}
+
+/// Context parameter to flush the output at component boundaries, see [Context::flush()].
+pub(crate) struct Flush(Rc<Sink>);
+
+impl Flush {
+    pub(crate) fn flush(&self) -> fmt::Result {
+        self.0.flush()
+    }
+}
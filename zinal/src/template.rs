@@ -1,4 +1,7 @@
-use crate::{html::HtmlEscaper, Children, Context, EmptyChildren, Escaper};
+use crate::{
+    html::HtmlEscaper, io::VecWriter, Children, Context, EmptyChildren, Escaper, IoWriter,
+    RenderError,
+};
 
 /// Trait implemented by types representing a template.
 ///
@@ -37,19 +40,26 @@ pub trait Template: Sized {
     /// # #[template(content = "")]
     /// # struct FooTemplate { name: String, age: u8 }
     /// # let mut context = zinal::Context::new();
-    /// FooTemplate::builder().name("John".into()).age(42).build(&context)
+    /// FooTemplate::builder().name("John".into()).age(42).try_build(&context)?
     /// # ;
+    /// # Ok::<(), zinal::MissingContextParam>(())
     /// ```
     ///
     /// As such, the builder must implement the following requirements:
     /// * for each attribute the template should support, there
     ///   needs to be a setter method with the same name, and taking
     ///   a value of the attribute type.
-    /// * a method named build(context: &mut RenderContext) that creates and
-    ///   returns the template with the previously set properties. The build
-    ///   method should require that all required properties were previously
-    ///   set. The render context is passed to the build method, so that any
-    ///   context parameters can be set.
+    /// * for each named slot the template requires, a method named
+    ///   `__zinal_slot_<name>` taking no arguments, that marks the slot
+    ///   as provided. The build method should require that all required
+    ///   slots were marked.
+    /// * a method named `try_build(context: &Context)` that creates the
+    ///   template with the previously set properties and returns it as
+    ///   `Result<Self, MissingContextParam>`. The method should require that
+    ///   all required properties were previously set. The context is passed
+    ///   to the method, so that any context parameters can be set, and a
+    ///   [MissingContextParam](crate::MissingContextParam) error is returned
+    ///   if a required context parameter is missing.
     ///
     /// Usually this will be implemented automatically by deriving
     /// the Template trait. When implementing the trait manually,
@@ -72,14 +82,17 @@ pub trait Template: Sized {
         escaper: &dyn Escaper,
         context: &Context,
         children: impl Children,
-    ) -> Result<(), std::fmt::Error>;
+    ) -> Result<(), RenderError>;
 
     /// Render this template to a string using the render() method.
     ///
+    /// The template is escaped as HTML, unless it declares a different escape
+    /// mode with `#[template(escape = ...)]` or by its file extension.
+    ///
     /// # Errors
     ///
     /// This function will return an error if the render() method returns an error.
-    fn render_to_string(self) -> Result<String, std::fmt::Error> {
+    fn render_to_string(self) -> Result<String, RenderError> {
         let mut buf = String::new();
         let escaper = HtmlEscaper;
         let context = Context::new();
@@ -89,6 +102,64 @@ pub trait Template: Sized {
         Ok(buf)
     }
 
+    /// Render this template into the given [std::io::Write] using the render() method.
+    ///
+    /// The output is buffered, so the writer doesn't need to be buffered itself.
+    ///
+    /// # Errors
+    ///
+    /// This function will return the error of the writer if writing fails, or
+    /// an error if the render() method returns an error otherwise.
+    fn render_to_writer(self, writer: impl std::io::Write) -> Result<(), std::io::Error> {
+        let mut writer = IoWriter::new(writer);
+        let escaper = HtmlEscaper;
+        let context = Context::new();
+
+        let result = self.render(&mut writer, &escaper, &context, EmptyChildren);
+
+        writer.finish(result)
+    }
+
+    /// Render this template to a byte vector using the render() method.
+    ///
+    /// # Errors
+    ///
+    /// This function will return an error if the render() method returns an error.
+    fn render_to_vec(self) -> Result<Vec<u8>, RenderError> {
+        let mut output = Vec::new();
+        let escaper = HtmlEscaper;
+        let context = Context::new();
+
+        self.render(
+            &mut VecWriter(&mut output),
+            &escaper,
+            &context,
+            EmptyChildren,
+        )?;
+
+        Ok(output)
+    }
+
+    /// Render this template as a stream of chunks, see the [stream](crate::stream) module.
+    ///
+    /// The template is rendered using a buffer size of
+    /// [DEFAULT_BUFFER_SIZE](crate::stream::DEFAULT_BUFFER_SIZE). Use
+    /// [RenderStream::new()](crate::stream::RenderStream::new) to choose a different buffer size.
+    ///
+    /// **Every call spawns a new OS thread** that renders the template and
+    /// blocks while the consumer is not keeping up with the output. To bound
+    /// the number of rendering threads, for example in a server, use
+    /// [RenderStream::with_spawner()](crate::stream::RenderStream::with_spawner)
+    /// with a thread pool instead.
+    #[cfg(feature = "stream")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "stream")))]
+    fn render_to_stream(self) -> crate::stream::RenderStream
+    where
+        Self: Send + 'static,
+    {
+        crate::stream::RenderStream::new(self, crate::stream::DEFAULT_BUFFER_SIZE)
+    }
+
     /// Create and return a builder for this template.
     fn builder() -> Self::Builder;
 }
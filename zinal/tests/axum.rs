@@ -0,0 +1,77 @@
+#![cfg(feature = "axum")]
+
+use std::{
+    pin::pin,
+    task::{Context, Poll, Waker},
+};
+
+use axum::{
+    body::{Body, HttpBody},
+    http::{header::CONTENT_TYPE, StatusCode},
+    response::{Html, IntoResponse},
+};
+use zinal::Template;
+
+#[derive(Template)]
+#[template("<p>{{ self.count.parse::<u32>()? }}</p>")]
+struct Count {
+    count: &'static str,
+}
+
+#[derive(Template)]
+#[template("{{ self.count }}", escape = "none")]
+struct PlainCount {
+    count: u32,
+}
+
+#[test]
+fn rendered_responses() {
+    let response = Count { count: "3" }.into_response();
+
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        "text/html; charset=utf-8",
+        response.headers()[CONTENT_TYPE].to_str().unwrap()
+    );
+
+    let response = PlainCount { count: 3 }.into_response();
+
+    assert_eq!(StatusCode::OK, response.status());
+    assert_eq!(
+        "text/plain; charset=utf-8",
+        response.headers()[CONTENT_TYPE].to_str().unwrap()
+    );
+}
+
+#[test]
+fn render_errors_are_internal_server_errors() {
+    let response = Count { count: "many" }.into_response();
+
+    assert_eq!(StatusCode::INTERNAL_SERVER_ERROR, response.status());
+}
+
+#[test]
+fn body_conversions() {
+    let response = Html(Count { count: "3" }).into_response();
+
+    assert_eq!(StatusCode::OK, response.status());
+
+    let mut body = pin!(Body::from(Count { count: "3" }));
+    let mut cx = Context::from_waker(Waker::noop());
+
+    let Poll::Ready(Some(Ok(frame))) = body.as_mut().poll_frame(&mut cx) else {
+        panic!("expected the rendered output");
+    };
+    assert_eq!(b"<p>3</p>".as_slice(), frame.into_data().unwrap());
+}
+
+#[test]
+fn render_errors_fail_bodies() {
+    let mut body = pin!(Body::from(Count { count: "many" }));
+    let mut cx = Context::from_waker(Waker::noop());
+
+    assert!(matches!(
+        body.as_mut().poll_frame(&mut cx),
+        Poll::Ready(Some(Err(_)))
+    ));
+}
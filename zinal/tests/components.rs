@@ -23,11 +23,39 @@ fn nested_components() {
     }
 
     assert_eq!(
-        Ok(String::from("true = true")),
-        Outer { bool_prop: true }.render_to_string()
+        String::from("true = true"),
+        Outer { bool_prop: true }.render_to_string().unwrap()
     );
     assert_eq!(
-        Ok(String::from("false = false")),
-        Outer { bool_prop: false }.render_to_string()
+        String::from("false = false"),
+        Outer { bool_prop: false }.render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn named_slots() {
+    #[derive(Template)]
+    #[template("<Card title={{self.title}}><:header>[{{self.title}}]</:header>Body<:footer>Footer</:footer></Card>")]
+    struct Page<'a> {
+        title: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template("<h1>{{self.title}}: {{@slot header}}</h1><main>{{@children}}</main><footer>{{@slot? footer}}</footer>")]
+    struct Card<'a> {
+        title: &'a str,
+    }
+
+    #[derive(Template)]
+    #[template("<Card title='Minimal'><:header>Header</:header></Card>")]
+    struct Minimal;
+
+    assert_eq!(
+        String::from("<h1>Hello: [Hello]</h1><main>Body</main><footer>Footer</footer>"),
+        Page { title: "Hello" }.render_to_string().unwrap()
+    );
+    assert_eq!(
+        String::from("<h1>Minimal: Header</h1><main></main><footer></footer>"),
+        Minimal.render_to_string().unwrap()
     );
 }
@@ -20,6 +20,83 @@ pub fn required_params() {
     assert_eq!(rendered, "10");
 }
 
+#[test]
+pub fn optional_params() {
+    #[derive(Template)]
+    #[template("<Greeting />")]
+    struct WithName {
+        #[provide_context]
+        name: String,
+    }
+
+    #[derive(Template)]
+    #[template("<Greeting />")]
+    struct WithoutName;
+
+    #[derive(Template)]
+    #[template("Hello, {{self.name.map(String::as_str).unwrap_or(\"stranger\")}}")]
+    struct Greeting<'a> {
+        #[from_context]
+        name: Option<&'a String>,
+    }
+
+    assert_eq!(
+        render(|| WithName {
+            name: "Mary".to_owned()
+        }),
+        "Hello, Mary"
+    );
+    assert_eq!(render(|| WithoutName), "Hello, stranger");
+}
+
+#[test]
+pub fn default_params() {
+    #[derive(Template)]
+    #[template("<Inner />")]
+    struct Outer;
+
+    #[derive(Template)]
+    #[template("{{self.count}}")]
+    struct Inner<'a> {
+        #[from_context(default = &42)]
+        count: &'a u8,
+    }
+
+    let rendered = render(|| Outer);
+    assert_eq!(rendered, "42");
+}
+
+#[test]
+pub fn missing_params() {
+    #[derive(Template)]
+    #[template("<main><Inner /></main>")]
+    struct Outer;
+
+    #[derive(Template)]
+    #[template("{{self.count}}")]
+    struct Inner<'a> {
+        #[from_context]
+        count: &'a u8,
+    }
+
+    let error = Outer.render_to_string().unwrap_err();
+
+    assert_eq!(
+        Some(&MissingContextParam {
+            template: "Inner",
+            field: "count"
+        }),
+        error.error().downcast_ref()
+    );
+    assert_eq!(
+        "Missing context parameter count of template Inner, in Outer",
+        error.to_string()
+    );
+
+    let context = Context::new();
+    assert!(Inner::builder().try_build(&context).is_err());
+}
+
 fn render<T: Template>(builder: impl FnOnce() -> T) -> String {
     builder()
         .render_to_string()
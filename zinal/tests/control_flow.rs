@@ -0,0 +1,212 @@
+use zinal::*;
+
+#[test]
+fn match_statement() {
+    enum State {
+        Loading,
+        Failed(&'static str),
+        Done(u32),
+    }
+
+    #[derive(Template)]
+    #[template(
+        "<#match self.state#>
+            <#case State::Loading => #>Loading...
+            <#case State::Failed(reason) => #>Failed: {{reason}}
+            <#case State::Done(count) if count == 1 => #>One item
+            <#case State::Done(count) => #>{{count}} items
+        <#end>"
+    )]
+    struct Status {
+        state: State,
+    }
+
+    let render = |state| {
+        Status { state }
+            .render_to_string()
+            .expect("should render without error")
+            .trim()
+            .to_owned()
+    };
+
+    assert_eq!(render(State::Loading), "Loading...");
+    assert_eq!(
+        render(State::Failed("<timeout>")),
+        "Failed: &lt;timeout&gt;"
+    );
+    assert_eq!(render(State::Done(1)), "One item");
+    assert_eq!(render(State::Done(3)), "3 items");
+
+    #[derive(Template)]
+    #[template(
+        "<#match self.state#><#case State::Done(count) =>#><#if count > 1#>many<#else#>one<#end#><#case _ =>#>none<#end#>"
+    )]
+    struct Nested {
+        state: State,
+    }
+
+    let render = |state| {
+        Nested { state }
+            .render_to_string()
+            .expect("should render without error")
+    };
+
+    assert_eq!(render(State::Done(3)), "many");
+    assert_eq!(render(State::Done(1)), "one");
+    assert_eq!(render(State::Loading), "none");
+}
+
+#[test]
+fn let_bindings() {
+    #[derive(Template)]
+    #[template(
+        "<#let total: u32 = self.prices.iter().sum()#>\
+         <#for price in &self.prices#><#let share = price * 100 / total#>{{share}}% <#end>\
+         of {{total}}"
+    )]
+    struct Summary {
+        prices: Vec<u32>,
+    }
+
+    assert_eq!(
+        String::from("25% 75% of 40"),
+        Summary {
+            prices: vec![10, 30]
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn loop_control() {
+    #[derive(Template)]
+    #[template(
+        "<#'rows: for row in &self.rows#>\
+           <#for cell in row#>\
+             <#if *cell == 0#><#continue 'rows#><#end>\
+             <#if *cell < 0#><#break 'rows#><#end>\
+             {{cell}}\
+           <#end>;\
+         <#end>"
+    )]
+    struct Table {
+        rows: Vec<Vec<i32>>,
+    }
+
+    assert_eq!(
+        String::from("12;3"),
+        Table {
+            rows: vec![vec![1, 2], vec![3, 0, 4], vec![-1], vec![5]]
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn braces_and_strings_in_code() {
+    #[derive(Template)]
+    #[template(r#"<# let open = "<#"; #>{{ format!("{{{}}}", self.value) }}{{ open }}{{ "}" }}"#)]
+    struct Braces {
+        value: u32,
+    }
+
+    assert_eq!(
+        String::from("{42}&lt;#}"),
+        Braces { value: 42 }.render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn whitespace_control() {
+    #[derive(Template)]
+    #[template(
+        "<ul>
+  <#- for name in &self.names -#>
+  <li>{{ *name }}</li>
+  <#- end #>
+</ul>"
+    )]
+    struct List {
+        names: Vec<&'static str>,
+    }
+
+    assert_eq!(
+        String::from("<ul><li>a</li><li>b</li>\n</ul>"),
+        List {
+            names: vec!["a", "b"]
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn trim_blocks() {
+    #[derive(Template)]
+    #[template(
+        content = "<pre>
+<#for line in &self.lines#>
+{{ *line }}
+<#end#>
+</pre>",
+        trim_blocks = true
+    )]
+    struct Lines {
+        lines: Vec<&'static str>,
+    }
+
+    assert_eq!(
+        String::from("<pre>\na\nb\n</pre>"),
+        Lines {
+            lines: vec!["a", "b"]
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn raw_block() {
+    #[derive(Template)]
+    #[template("<#raw#><div x-text=\"{{ message }}\"><Comp /></div><#end#>{{ self.n }}")]
+    struct Raw {
+        n: u8,
+    }
+
+    assert_eq!(
+        String::from("<div x-text=\"{{ message }}\"><Comp /></div>7"),
+        Raw { n: 7 }.render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn comments() {
+    #[derive(Template)]
+    #[template(
+        content = "<#-- Only visible in the template --#><!-- HTML --><p>{{ self.n }}</p>",
+        strip_html_comments = false
+    )]
+    struct Kept {
+        n: u8,
+    }
+
+    #[derive(Template)]
+    #[template(
+        content = "<!-- HTML --><!--[if IE]>IE<![endif]--><#-- Template --#><p>{{ self.n }}</p>",
+        strip_html_comments = true
+    )]
+    struct Stripped {
+        n: u8,
+    }
+
+    assert_eq!(
+        String::from("<!-- HTML --><p>1</p>"),
+        Kept { n: 1 }.render_to_string().unwrap()
+    );
+    assert_eq!(
+        String::from("<!--[if IE]>IE<![endif]--><p>2</p>"),
+        Stripped { n: 2 }.render_to_string().unwrap()
+    );
+}
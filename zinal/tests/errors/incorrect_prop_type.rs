@@ -9,7 +9,7 @@ struct Outer {
 #[derive(Template)]
 #[template("{{self.prop}}")]
 struct Inner {
-    prop: String,
+    prop: bool,
 }
 
 fn main() {
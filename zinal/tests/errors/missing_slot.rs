@@ -0,0 +1,16 @@
+use zinal::*;
+
+#[derive(Template)]
+#[template("<Card>Body</Card>")]
+struct Page;
+
+#[derive(Template)]
+#[template("<h1>{{@slot header}}</h1>{{@children}}")]
+struct Card;
+
+fn main() {
+    println!(
+        "{}",
+        Page.render_to_string().expect("Should not fail")
+    );
+}
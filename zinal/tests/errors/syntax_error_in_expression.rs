@@ -0,0 +1,13 @@
+use zinal::*;
+
+#[derive(Template)]
+#[template(
+    "<ul>
+  <li>{{ self.prop + }}</li>
+</ul>"
+)]
+struct Example {
+    prop: u8,
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+use zinal::*;
+
+#[derive(Template)]
+#[template(
+    "<div>
+  <p>{{ self.name </p>
+</div>"
+)]
+struct Example {
+    name: String,
+}
+
+fn main() {}
@@ -0,0 +1,221 @@
+use zinal::*;
+
+#[test]
+fn pre_escaped_values() {
+    #[derive(Template)]
+    #[template(
+        "<div>{{ self.html }}</div><div>{{! self.html }}</div>\
+         <div>{{ PreEscaped(&self.html) }}</div><div>{{! self.count:>3 }}</div>\
+         {{! self.footer }}{{! self.missing }}"
+    )]
+    struct Fragment {
+        html: String,
+        count: u32,
+        footer: Option<String>,
+        missing: Option<String>,
+    }
+
+    assert_eq!(
+        String::from(
+            "<div>&lt;b&gt;Hi&lt;/b&gt;</div><div><b>Hi</b></div><div><b>Hi</b></div><div>  7</div><hr>"
+        ),
+        Fragment {
+            html: String::from("<b>Hi</b>"),
+            count: 7,
+            footer: Some(String::from("<hr>")),
+            missing: None,
+        }
+        .render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn context_aware_escaping() {
+    #[derive(Template)]
+    #[template(
+        "<a href=\"{{ self.link }}\" title={{ self.text }}>{{ self.text }}</a>\
+         <img src=\"/users/{{ self.text }}.png\">\
+         <script>let text = {{ self.text }}; let quoted = '{{ self.text }}';</script>\
+         <style>p { font-family: {{ self.text }}; }</style><p>{{ self.text }}</p>"
+    )]
+    struct Page {
+        link: &'static str,
+        text: &'static str,
+    }
+
+    let page = Page {
+        link: "javascript:alert(1)",
+        text: "a'b </script>",
+    };
+
+    assert_eq!(
+        String::from(
+            "<a href=\"about:invalid\" title=a&#x27;b&#x20;&#x3C;&#x2F;script&#x3E;>a&apos;b &lt;/script&gt;</a>\
+             <img src=\"/users/a%27b%20%3C/script%3E.png\">\
+             <script>let text = \"a\\u0027b \\u003C\\u002Fscript\\u003E\"; \
+             let quoted = 'a\\u0027b \\u003C\\u002Fscript\\u003E';</script>\
+             <style>p { font-family: a\\27 b \\3C \\2F script\\3E ; }</style>\
+             <p>a&apos;b &lt;/script&gt;</p>"
+        ),
+        page.render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn safe_urls() {
+    #[derive(Template)]
+    #[template("<a href=\"{{ self.link }}\">Link</a>")]
+    struct Link {
+        link: &'static str,
+    }
+
+    assert_eq!(
+        String::from("<a href=\"https://example.com/?a=1&amp;b=2\">Link</a>"),
+        Link {
+            link: "https://example.com/?a=1&b=2"
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn event_handler_and_style_attributes() {
+    #[derive(Template)]
+    #[template(
+        "<a onclick=\"go('{{ self.text }}')\" onmouseover=\"show({{ self.text }})\" \
+         style=\"color: {{ self.text }}\">Link</a>"
+    )]
+    struct Link {
+        text: &'static str,
+    }
+
+    assert_eq!(
+        String::from(
+            "<a onclick=\"go('&#x5C;u0027&#x29;&#x3B;alert&#x28;1&#x29;&#x3B;&#x5C;u002F&#x5C;u002F')\" \
+             onmouseover=\"show(&#x22;&#x5C;u0027&#x29;&#x3B;alert&#x28;1&#x29;&#x3B;&#x5C;u002F&#x5C;u002F&#x22;)\" \
+             style=\"color: &#x5C;27&#x20;&#x5C;29&#x20;&#x5C;3B&#x20;alert&#x5C;28&#x20;1&#x5C;29&#x20;&#x5C;3B&#x20;&#x5C;2F&#x20;&#x5C;2F&#x20;\">Link</a>"
+        ),
+        Link {
+            text: "');alert(1);//"
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn script_values() {
+    #[derive(Template)]
+    #[template(
+        "<script>let r = /\"/; let s = {{ self.text }}; let n = {{ self.count }}; \
+         let l = {{ join(&self.list, \", \") }};</script>"
+    )]
+    struct Script {
+        text: &'static str,
+        count: u32,
+        list: Vec<&'static str>,
+    }
+
+    assert_eq!(
+        String::from(
+            "<script>let r = /\"/; let s = \"1;alert(1)\\u002F\\u002F\"; let n = 3; \
+             let l = \"a, b\";</script>"
+        ),
+        Script {
+            text: "1;alert(1)//",
+            count: 3,
+            list: vec!["a", "b"],
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn escape_modes() {
+    #[derive(Template)]
+    #[template(path = "escaping/mail.txt")]
+    struct Mail {
+        name: &'static str,
+        order: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template(
+        "<item><title>{{ self.title }}</title><script>{{ self.title }}</script></item>",
+        escape = "xml"
+    )]
+    struct Item {
+        title: &'static str,
+    }
+
+    assert_eq!(
+        String::from("Hello Mary & John, your order <#12> has shipped.\n"),
+        Mail {
+            name: "Mary & John",
+            order: "#12"
+        }
+        .render_to_string()
+        .unwrap()
+    );
+
+    assert_eq!(
+        String::from("<item><title>Tom &amp; Jerry</title><script>Tom &amp; Jerry</script></item>"),
+        Item {
+            title: "Tom & Jerry"
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn custom_escaper() {
+    struct LatexEscaper;
+
+    impl Escaper for LatexEscaper {
+        fn escape(&self, value: &str, writer: &mut dyn std::fmt::Write) -> std::fmt::Result {
+            for c in value.chars() {
+                if matches!(c, '&' | '%') {
+                    writer.write_char('\\')?;
+                }
+                writer.write_char(c)?;
+            }
+            Ok(())
+        }
+    }
+
+    #[derive(Template)]
+    #[template("\\section{ {{ self.title }} }", escape = LatexEscaper)]
+    struct Section {
+        title: &'static str,
+    }
+
+    assert_eq!(
+        String::from("\\section{ 100\\% Tom \\& Jerry }"),
+        Section {
+            title: "100% Tom & Jerry"
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn children_use_escaper_of_parent() {
+    #[derive(Template)]
+    #[template("[{{@children}}]", escape = "none")]
+    struct Plain;
+
+    #[derive(Template)]
+    #[template("<p><Plain>{{ self.text }}</Plain></p>")]
+    struct Page {
+        text: &'static str,
+    }
+
+    assert_eq!(
+        String::from("<p>[&lt;b&gt;]</p>"),
+        Page { text: "<b>" }.render_to_string().unwrap()
+    );
+}
@@ -0,0 +1,153 @@
+use zinal::*;
+
+#[test]
+fn builtin_filters() {
+    #[derive(Template)]
+    #[template(
+        "<h1>{{ self.title | trim | upper | truncate(8) }}</h1>\
+         <p>{{ self.tags | join(\", \") | lower }}</p>\
+         <p>{{ self.author | default(\"Anonymous\") }}</p>\
+         <a href=\"/search?q={{ self.title | trim | urlencode }}\">\
+         {{ self.count }} {{ self.count | pluralize(\"result\", \"results\") }}</a>"
+    )]
+    struct Article {
+        title: String,
+        tags: Vec<&'static str>,
+        author: Option<String>,
+        count: u32,
+    }
+
+    let article = Article {
+        title: String::from(" Fish & Chips "),
+        tags: vec!["Food", "UK"],
+        author: None,
+        count: 1,
+    };
+
+    assert_eq!(
+        String::from(
+            "<h1>FISH &amp; C...</h1>\
+             <p>food, uk</p>\
+             <p>Anonymous</p>\
+             <a href=\"/search?q=Fish%20%26%20Chips\">1 result</a>"
+        ),
+        article.render_to_string().unwrap()
+    );
+}
+
+fn exclaim(value: &str, count: usize) -> String {
+    format!("{}{}", value, "!".repeat(count))
+}
+
+#[test]
+fn custom_filters() {
+    #[derive(Template)]
+    #[template("<p>{{ self.greeting | exclaim(3) | upper }} {{ (self.flags | 0x4) }}</p>")]
+    struct Greeting {
+        greeting: String,
+        flags: u8,
+    }
+
+    let greeting = Greeting {
+        greeting: String::from("Hello"),
+        flags: 0x1,
+    };
+
+    assert_eq!(
+        String::from("<p>HELLO!!! 5</p>"),
+        greeting.render_to_string().unwrap()
+    );
+}
+
+mod shadowed {
+    use zinal::*;
+
+    /// Shadows the built-in upper filter
+    fn upper(value: &str) -> String {
+        format!("<{}>", value)
+    }
+
+    #[derive(Template)]
+    #[template("<p>{{ self.text | upper }} {{ self.text | lower }}</p>")]
+    pub struct Text {
+        pub text: &'static str,
+    }
+}
+
+#[test]
+fn custom_filters_shadow_builtin_filters() {
+    assert_eq!(
+        String::from("<p>&lt;Hi&gt; hi</p>"),
+        shadowed::Text { text: "Hi" }.render_to_string().unwrap()
+    );
+}
+
+const MASK: u8 = 0x2;
+
+#[test]
+fn bitwise_or() {
+    #[derive(Template)]
+    #[template(
+        "<# let other = self.other; #><p>{{ self.flags | MASK }} {{ self.flags | 0x4 }} \
+         {{ (self.flags | self.other) }} {{ self.flags | other }}</p>"
+    )]
+    struct Flags {
+        flags: u8,
+        other: u8,
+    }
+
+    assert_eq!(
+        String::from("<p>3 5 9 9</p>"),
+        Flags {
+            flags: 0x1,
+            other: 0x8
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn filters_in_template_arguments() {
+    #[derive(Template)]
+    #[template("<Badge label={{ self.label | upper }} />")]
+    struct Page {
+        label: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template("<span>{{ self.label }}</span>")]
+    struct Badge {
+        label: String,
+    }
+
+    assert_eq!(
+        String::from("<span>NEW</span>"),
+        Page { label: "new" }.render_to_string().unwrap()
+    );
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn json_filter() {
+    #[derive(Template)]
+    #[template(
+        "<div data-tags=\"{{ self.tags | json }}\"></div>\
+         <script>let tags = {{ self.tags | json }};</script>"
+    )]
+    struct Tags {
+        tags: Vec<&'static str>,
+    }
+
+    assert_eq!(
+        String::from(
+            "<div data-tags=\"[&quot;a&quot;,&quot;b&quot;]\"></div>\
+             <script>let tags = [\"a\",\"b\"];</script>"
+        ),
+        Tags {
+            tags: vec!["a", "b"]
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
@@ -0,0 +1,29 @@
+use zinal::*;
+
+#[test]
+fn format_specs() {
+    #[derive(Template)]
+    #[template(
+        "<td>{{ self.price:.2 }}</td><td>{{ self.name:>6 }}</td>\
+         <td>{{ self.id:#x }}</td><td>{{ self.tags:? }}</td><#let width = 6#><td>{{ self.price:>width$.1 }}</td>"
+    )]
+    struct Row {
+        price: f64,
+        name: &'static str,
+        id: u32,
+        tags: Vec<&'static str>,
+    }
+
+    assert_eq!(
+        String::from(
+            "<td>12.35</td><td>   &lt;b&gt;</td><td>0xff</td><td>[&quot;a&quot;]</td><td>  12.3</td>"
+        ),
+        Row {
+            price: 12.3456,
+            name: "<b>",
+            id: 255,
+            tags: vec!["a"],
+        }
+        .render_to_string().unwrap()
+    );
+}
@@ -0,0 +1,23 @@
+#![cfg(feature = "hot-reload")]
+
+use zinal::*;
+
+#[test]
+fn unchanged_template_uses_compiled_code() {
+    #[derive(Template)]
+    #[template(path = "hot_reload/profile.html")]
+    struct Profile {
+        name: String,
+        tags: Vec<String>,
+    }
+
+    assert_eq!(
+        String::from("<p>Mary is admin, editor</p>\n"),
+        Profile {
+            name: String::from("Mary"),
+            tags: vec![String::from("admin"), String::from("editor")],
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
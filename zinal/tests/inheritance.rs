@@ -0,0 +1,34 @@
+use zinal::*;
+
+#[test]
+fn extends_base_template() {
+    #[derive(Template)]
+    #[template("<#extends \"inheritance/base.html\"#><#block title#>{{self.title}}<#end#>")]
+    struct Page<'a> {
+        title: &'a str,
+    }
+
+    assert_eq!(
+        String::from("<html><head><title>Hello</title></head><body></body></html>\n"),
+        Page { title: "Hello" }.render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn extends_nested_templates() {
+    #[derive(Template)]
+    #[template("<#extends \"inheritance/page.html\"#><#block content#>{{self.content}}<#end#>")]
+    struct Article<'a> {
+        content: &'a str,
+    }
+
+    assert_eq!(
+        String::from(
+            "<html><head><title>Default title</title></head><body><main>Lorem ipsum</main></body></html>\n"
+        ),
+        Article {
+            content: "Lorem ipsum"
+        }
+        .render_to_string().unwrap()
+    );
+}
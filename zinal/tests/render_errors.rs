@@ -0,0 +1,113 @@
+use std::{fmt, num::ParseIntError};
+
+use zinal::*;
+
+#[derive(Template)]
+#[template(path = "render_errors/list.html")]
+struct List<'a> {
+    items: &'a [&'a str],
+}
+
+#[derive(Template)]
+#[template("<main>\n<List items={{self.items}} />\n</main>")]
+struct Page<'a> {
+    items: &'a [&'a str],
+}
+
+#[test]
+fn errors_in_expressions() {
+    let error = Page {
+        items: &["1", "two"],
+    }
+    .render_to_string()
+    .unwrap_err();
+
+    assert!(error.error().is::<ParseIntError>());
+    assert_eq!(&["Page", "List"], error.components());
+    assert_eq!(
+        Some(&Location {
+            file: Some("templates/render_errors/list.html"),
+            line: 3,
+            column: 12
+        }),
+        error.location()
+    );
+    assert_eq!(
+        "invalid digit found in string, in Page > List (templates/render_errors/list.html:3:12)",
+        error.to_string()
+    );
+}
+
+#[test]
+fn errors_in_statements() {
+    #[derive(Template)]
+    #[template("<# let port: u16 = self.port.parse()?; #>:{{port}}")]
+    struct Port<'a> {
+        port: &'a str,
+    }
+
+    let error = Port { port: "http" }.render_to_string().unwrap_err();
+
+    assert!(error.error().is::<ParseIntError>());
+    assert_eq!(
+        Some(&Location {
+            file: None,
+            line: 1,
+            column: 8
+        }),
+        error.location()
+    );
+}
+
+#[test]
+fn errors_in_children() {
+    #[derive(Template)]
+    #[template("<section>{{@children}}</section>")]
+    struct Section;
+
+    #[derive(Template)]
+    #[template("<Section>\n  {{ self.count.parse::<u8>()? }}\n</Section>")]
+    struct Counter<'a> {
+        count: &'a str,
+    }
+
+    let error = Counter { count: "-1" }.render_to_string().unwrap_err();
+
+    assert_eq!(&["Counter", "Section"], error.components());
+    assert_eq!(
+        Some(&Location {
+            file: None,
+            line: 2,
+            column: 6
+        }),
+        error.location()
+    );
+}
+
+#[test]
+fn errors_from_renderables() {
+    struct Unavailable;
+
+    impl Renderable for Unavailable {
+        fn render(
+            &self,
+            _writer: &mut dyn fmt::Write,
+            _escaper: &dyn Escaper,
+        ) -> Result<(), RenderError> {
+            Err(RenderError::new("database unavailable"))
+        }
+    }
+
+    #[derive(Template)]
+    #[template("<p>{{ Unavailable }}</p>")]
+    struct Status;
+
+    let error = Status.render_to_string().unwrap_err();
+    assert_eq!("database unavailable", error.error().to_string());
+
+    let error = Status.render_to_writer(Vec::new()).unwrap_err();
+    assert_eq!(
+        "database unavailable, in Status (line 1, column 7)",
+        error.to_string()
+    );
+}
@@ -0,0 +1,143 @@
+#![cfg(feature = "stream")]
+
+use std::{
+    future::Future,
+    pin::pin,
+    sync::Arc,
+    task::{Context, Poll, Wake, Waker},
+    thread::Thread,
+};
+
+use futures_core::Stream;
+use zinal::{stream::RenderStream, Template};
+
+#[derive(Template)]
+#[template(
+    "<html><head><title>Report</title></head><body><Rows count={{self.count}} /></body></html>"
+)]
+struct Report {
+    count: usize,
+}
+
+#[derive(Template)]
+#[template("<#for i in 0..self.count#><p>{{i}}</p><#end#>")]
+struct Rows {
+    count: usize,
+}
+
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn block_on<F: Future>(future: F) -> F::Output {
+    let waker = Waker::from(Arc::new(ThreadWaker(std::thread::current())));
+    let mut cx = Context::from_waker(&waker);
+    let mut future = pin!(future);
+
+    loop {
+        match future.as_mut().poll(&mut cx) {
+            Poll::Ready(output) => return output,
+            Poll::Pending => std::thread::park(),
+        }
+    }
+}
+
+fn collect(stream: RenderStream) -> Vec<Vec<u8>> {
+    let mut stream = pin!(stream);
+    let mut chunks = Vec::new();
+
+    while let Some(chunk) = block_on(std::future::poll_fn(|cx| stream.as_mut().poll_next(cx))) {
+        chunks.push(chunk.unwrap().to_vec());
+    }
+
+    chunks
+}
+
+#[test]
+fn stream_chunks() {
+    let chunks = collect(RenderStream::new(Report { count: 100 }, 64));
+
+    assert_eq!(
+        b"<html><head><title>Report</title></head><body>".to_vec(),
+        chunks[0],
+        "Content before a child template should be sent separately"
+    );
+    assert!(chunks[1..chunks.len() - 1].iter().all(|c| c.len() >= 64));
+
+    let output = String::from_utf8(chunks.concat()).unwrap();
+    let expected = Report { count: 100 }.render_to_string().unwrap();
+    assert_eq!(expected, output);
+}
+
+#[test]
+fn stream_with_default_buffer_size() {
+    let chunks = collect(Report { count: 3 }.render_to_stream());
+
+    assert_eq!(
+        "<html><head><title>Report</title></head><body><p>0</p><p>1</p><p>2</p></body></html>",
+        String::from_utf8(chunks.concat()).unwrap()
+    );
+}
+
+#[test]
+fn stream_with_spawner() {
+    let (sender, receiver) = std::sync::mpsc::channel::<Box<dyn FnOnce() + Send>>();
+    let worker = std::thread::spawn(move || receiver.into_iter().for_each(|render| render()));
+
+    let stream = RenderStream::with_spawner(Report { count: 1 }, 64, move |render| {
+        sender.send(render).unwrap();
+    });
+
+    assert_eq!(
+        "<html><head><title>Report</title></head><body><p>0</p></body></html>",
+        String::from_utf8(collect(stream).concat()).unwrap()
+    );
+    worker.join().unwrap();
+}
+
+#[test]
+fn stream_render_errors() {
+    #[derive(Template)]
+    #[template("<p>before</p><Count count={{self.count}} />")]
+    struct Page {
+        count: &'static str,
+    }
+
+    #[derive(Template)]
+    #[template("<p>{{ self.count.parse::<u32>()? }}</p>")]
+    struct Count {
+        count: &'static str,
+    }
+
+    let stream = Page { count: "many" }.render_to_stream();
+    let mut stream = pin!(stream);
+    let mut items = Vec::new();
+
+    while let Some(item) = block_on(std::future::poll_fn(|cx| stream.as_mut().poll_next(cx))) {
+        items.push(item);
+    }
+
+    assert_eq!(2, items.len());
+    assert_eq!(b"<p>before</p>".as_slice(), items[0].as_ref().unwrap());
+    assert_eq!(
+        &["Page", "Count"],
+        items[1].as_ref().unwrap_err().components()
+    );
+    assert!(stream.failed());
+}
+
+#[cfg(feature = "http-body")]
+#[test]
+fn stream_axum_body() {
+    let body = axum::body::Body::new(Report { count: 2 }.render_to_stream());
+    let bytes = block_on(axum::body::to_bytes(body, usize::MAX)).unwrap();
+
+    assert_eq!(
+        b"<html><head><title>Report</title></head><body><p>0</p><p>1</p></body></html>".as_slice(),
+        bytes.as_ref()
+    );
+}
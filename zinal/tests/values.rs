@@ -0,0 +1,107 @@
+use std::num::{NonZeroI32, NonZeroUsize};
+
+use zinal::*;
+
+#[test]
+fn primitives() {
+    #[derive(Template)]
+    #[template(
+        "{{ self.count }};{{ self.offset }};{{ self.ratio }};{{ self.half }};\
+         {{ self.separator }};{{ self.pages }};{{ self.delta }}"
+    )]
+    struct Primitives {
+        count: usize,
+        offset: isize,
+        ratio: f64,
+        half: f32,
+        separator: char,
+        pages: NonZeroUsize,
+        delta: NonZeroI32,
+    }
+
+    let primitives = Primitives {
+        count: 3,
+        offset: -2,
+        ratio: 1.25,
+        half: 0.5,
+        separator: '&',
+        pages: NonZeroUsize::new(7).unwrap(),
+        delta: NonZeroI32::new(-1).unwrap(),
+    };
+
+    assert_eq!(
+        String::from("3;-2;1.25;0.5;&amp;;7;-1"),
+        primitives.render_to_string().unwrap()
+    );
+}
+
+#[test]
+fn references() {
+    #[derive(Template)]
+    #[template("<#for name in &self.names#>{{ name }},<#end#>{{ &&self.title }}{{ self.initial }}")]
+    struct References<'a> {
+        names: Vec<&'a str>,
+        title: &'a String,
+        initial: &'a char,
+    }
+
+    let title = String::from("<Title>");
+
+    assert_eq!(
+        String::from("a,b,&lt;Title&gt;x"),
+        References {
+            names: vec!["a", "b"],
+            title: &title,
+            initial: &'x',
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn display_and_debug_adapters() {
+    #[derive(Template)]
+    #[template(
+        "{{ Display(&self.address) }} {{ Debug(&self.name) }} {{ format_args!(\"<{}>\", 1) }}"
+    )]
+    struct Adapters {
+        address: std::net::Ipv4Addr,
+        name: &'static str,
+    }
+
+    assert_eq!(
+        String::from("127.0.0.1 &quot;&lt;b&gt;&quot; &lt;1&gt;"),
+        Adapters {
+            address: std::net::Ipv4Addr::LOCALHOST,
+            name: "<b>",
+        }
+        .render_to_string()
+        .unwrap()
+    );
+}
+
+#[test]
+fn collections_and_tuples() {
+    #[derive(Template)]
+    #[template(
+        "{{ self.numbers }}|{{ &self.numbers[1..] }}|{{ self.pair }}|{{ ['<', '>'] }}|\
+         {{ join(&self.names, \", \") }}|{{ join(self.numbers.iter().map(|n| n * 2), '&') }}"
+    )]
+    struct Collections {
+        numbers: Vec<u32>,
+        pair: (&'static str, u8),
+        names: Vec<String>,
+    }
+
+    let collections = Collections {
+        numbers: vec![1, 2, 3],
+        pair: ("<a>", 1),
+        names: vec![String::from("Mary"), String::from("<John>")],
+    };
+
+    assert_eq!(
+        String::from("123|23|&lt;a&gt;1|&lt;&gt;|Mary, &lt;John&gt;|2&amp;4&amp;6"),
+        collections.render_to_string().unwrap()
+    );
+}
@@ -0,0 +1,61 @@
+use std::io;
+
+use zinal::*;
+
+#[derive(Template)]
+#[template("<ul><#for item in self.items#><li>{{ item }}</li><#end#></ul>")]
+struct List {
+    items: Vec<&'static str>,
+}
+
+#[test]
+fn render_to_writer() {
+    let mut output = Vec::new();
+
+    List {
+        items: vec!["a", "b & c"],
+    }
+    .render_to_writer(&mut output)
+    .unwrap();
+
+    assert_eq!(
+        "<ul><li>a</li><li>b &amp; c</li></ul>",
+        String::from_utf8(output).unwrap()
+    );
+}
+
+#[test]
+fn render_to_vec() {
+    assert_eq!(
+        b"<ul><li>a</li></ul>".to_vec(),
+        List { items: vec!["a"] }.render_to_vec().unwrap()
+    );
+    assert_eq!(
+        "<ul><li>Grüße</li><li>日本 &lt;3</li></ul>".as_bytes(),
+        List {
+            items: vec!["Grüße", "日本 <3"]
+        }
+        .render_to_vec()
+        .unwrap()
+    );
+}
+
+#[test]
+fn writer_errors_are_kept() {
+    struct Disconnected;
+
+    impl io::Write for Disconnected {
+        fn write(&mut self, _buf: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "disconnected"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let items = vec!["Lorem ipsum dolor sit amet"; 1000];
+    let error = List { items }.render_to_writer(Disconnected).unwrap_err();
+
+    assert_eq!(io::ErrorKind::BrokenPipe, error.kind());
+}
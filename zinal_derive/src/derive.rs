@@ -1,36 +1,56 @@
-use std::path::PathBuf;
+use std::{
+    cell::OnceCell,
+    path::{Path, PathBuf},
+};
 
 use proc_macro2::{Span, TokenStream};
 
-use syn::{Error, Ident, ItemStruct};
+use syn::{Error, Ident, ItemStruct, LitStr};
 
 use crate::{
-    opts::TemplateOptions,
-    parser::{self, Item},
+    opts::{EscapeMode, TemplateOptions},
+    parser::{self, Item, Origin},
 };
 
 mod builder;
 mod fields;
+mod filters;
 mod properties;
+mod slots;
 mod values;
 
 use builder::*;
 use fields::*;
+use filters::*;
 use properties::*;
+use slots::*;
 use values::*;
 
 pub(crate) fn derive(template: ItemStruct) -> Result<TokenStream, Error> {
     let options = TemplateOptions::from_struct(&template)?;
 
+    let sources = SourceChain::new(read_content(&options)?);
+    let items = parse_sources(&sources, &options)?;
+    let sources = sources.iter().collect::<Vec<_>>();
+
     let fields = TemplateFields::from_template(&template)?;
-    let properties = TemplateProperties::from_template(&template, &fields);
+    let slots = TemplateSlots::from_items(&items);
+    let filters = TemplateFilters::from_items(&template, &items);
+    let properties = TemplateProperties::from_template(&template, &fields, &slots);
     let values = TemplateValues::from_template(&template, &fields);
-    let builder = TemplateBuilder::from_template(&template, &fields, &values, &properties);
+    let builder = TemplateBuilder::from_template(&template, &fields, &slots, &values, &properties);
 
-    let template_impl = derive_template_impl(&template, &fields, &options, &builder)?;
+    let escaper = derive_escaper(&options);
+    let hot_reload = derive_hot_reload(&sources);
+    let template_impl = derive_template_impl(
+        &template, &options, &fields, items, escaper, hot_reload, &builder,
+    )?;
+    let dependencies = derive_dependencies(&sources);
 
     Ok(quote! {
+        #filters
         #template_impl
+        #dependencies
         #values
         #properties
         #builder
@@ -39,18 +59,19 @@ pub(crate) fn derive(template: ItemStruct) -> Result<TokenStream, Error> {
 
 fn derive_template_impl(
     template: &ItemStruct,
+    #[allow(unused_variables)] options: &TemplateOptions,
     fields: &TemplateFields,
-    options: &TemplateOptions,
+    items: Vec<Item<'_>>,
+    escaper: TokenStream,
+    hot_reload: TokenStream,
     builder: &TemplateBuilder<'_>,
 ) -> Result<TokenStream, Error> {
-    let content = read_content(options)?;
-
-    let items = parser::parse(&content)?;
     let items = Item::emit_all(items)?;
 
     let providers = derive_context_providers(fields);
 
     let ident = &template.ident;
+    let name = ident.to_string();
     let (impl_generics, ty_generics, where_clause) = template.generics.split_for_impl();
 
     let builder_ty = &builder.ident;
@@ -70,14 +91,28 @@ fn derive_template_impl(
                 __zinal_escaper: &dyn ::zinal::Escaper,
                 __zinal_context: &::zinal::Context,
                 __zinal_children: impl ::zinal::Children,
-            ) -> ::std::result::Result<(), ::std::fmt::Error> {
+            ) -> ::std::result::Result<(), ::zinal::RenderError> {
                 let mut __zinal_provided_context = ::zinal::Context::new();
                 #(#providers)*
                 let __zinal_context = &__zinal_context.extend(__zinal_provided_context);
 
-                #(#items)*
+                // The location of the item being rendered, to locate errors
+                let __zinal_location = ::std::cell::Cell::new(::std::option::Option::None);
+
+                // Report the component stack if rendering panics
+                #[cfg(debug_assertions)]
+                let __zinal_component = ::zinal::ComponentGuard::enter(#name, &__zinal_location);
+
+                let __zinal_result = (|| -> ::std::result::Result<(), ::zinal::RenderError> {
+                    #escaper
+                    #hot_reload
+
+                    #(#items)*
+
+                    Ok(())
+                })();
 
-                Ok(())
+                __zinal_result.map_err(|e| e.in_component(#name, __zinal_location.get()))
             }
 
             fn builder() -> Self::Builder {
@@ -87,20 +122,116 @@ fn derive_template_impl(
     });
 
     #[cfg(feature = "axum")]
-    expanded.extend(quote! {
+    {
+        let body = match options.escape_mode() {
+            None | Some(EscapeMode::Html) => quote!(::axum::response::Html(body)),
+            Some(_) => quote!(body),
+        };
+
+        expanded.extend(quote! {
+
+            // Respond with an internal server error if rendering fails,
+            // showing the error in debug builds
+            #[automatically_derived]
+            impl #impl_generics ::axum::response::IntoResponse for #ident #ty_generics #where_clause {
+                fn into_response(self) -> ::axum::response::Response {
+                    match ::zinal::Template::render_to_string(self) {
+                        ::std::result::Result::Ok(body) => {
+                            ::axum::response::IntoResponse::into_response(#body)
+                        }
+                        ::std::result::Result::Err(error) => {
+                            let message = if cfg!(debug_assertions) {
+                                ::std::string::ToString::to_string(&error)
+                            } else {
+                                ::std::string::String::from("Internal Server Error")
+                            };
+
+                            ::axum::response::IntoResponse::into_response((
+                                ::axum::http::StatusCode::INTERNAL_SERVER_ERROR,
+                                message,
+                            ))
+                        }
+                    }
+                }
+            }
 
-        #[automatically_derived]
-        impl #impl_generics ::core::convert::Into<::axum::body::Body> for #ident #ty_generics #where_clause {
-            fn into(self) -> ::axum::body::Body {
-                self.render_to_string().expect("Could not render template to string").into()
+            // Fail the body instead of panicking if rendering fails
+            #[automatically_derived]
+            impl #impl_generics ::core::convert::From<#ident #ty_generics> for ::axum::body::Body #where_clause {
+                fn from(template: #ident #ty_generics) -> Self {
+                    match ::zinal::Template::render_to_string(template) {
+                        ::std::result::Result::Ok(body) => ::axum::body::Body::from(body),
+                        ::std::result::Result::Err(error) => {
+                            ::axum::body::Body::new(::zinal::RenderErrorBody::new(error))
+                        }
+                    }
+                }
             }
-        }
-    });
+        });
+    }
 
     // Hand the output tokens back to the compiler
     Ok(expanded)
 }
 
+/// Replace the escaper the template is rendered with, if the template declares
+/// its output language. Otherwise the escaper given by the caller is used.
+fn derive_escaper(options: &TemplateOptions) -> TokenStream {
+    let escaper = match options.escape_mode() {
+        Some(EscapeMode::Html) => quote!(::zinal::html::HtmlEscaper),
+        Some(EscapeMode::Xml) => quote!(::zinal::XmlEscaper),
+        Some(EscapeMode::None) => quote!(::zinal::NoEscaper),
+        Some(EscapeMode::Custom(path)) => quote!(#path),
+        None => return TokenStream::new(),
+    };
+
+    quote! {
+        let __zinal_escaper: &dyn ::zinal::Escaper = &#escaper;
+    }
+}
+
+/// Register all template files as dependencies of the crate, so that changing
+/// one of them causes the crate to be recompiled.
+fn derive_dependencies(sources: &[&TemplateSource]) -> TokenStream {
+    let paths = sources
+        .iter()
+        .filter_map(|s| s.path.as_ref())
+        .map(|p| p.to_string_lossy());
+
+    quote! {
+        #(const _: &[u8] = ::core::include_bytes!(#paths);)*
+    }
+}
+
+/// Render an error page instead of outdated output for changed template files
+/// in debug builds.
+#[cfg(feature = "hot-reload")]
+fn derive_hot_reload(sources: &[&TemplateSource]) -> TokenStream {
+    let Some(paths) = sources
+        .iter()
+        .map(|s| s.path.as_ref().map(|p| p.to_string_lossy()))
+        .collect::<Option<Vec<_>>>()
+    else {
+        // Inline templates cannot change without recompiling
+        return TokenStream::new();
+    };
+
+    quote! {
+        #[cfg(debug_assertions)]
+        if ::zinal::hot_reload::render(
+            &[#((#paths, ::core::include_str!(#paths))),*],
+            __zinal_writer,
+        )? {
+            return Ok(());
+        }
+    }
+}
+
+#[cfg(not(feature = "hot-reload"))]
+fn derive_hot_reload(_sources: &[&TemplateSource]) -> TokenStream {
+    TokenStream::new()
+}
+
 fn derive_context_providers(fields: &TemplateFields) -> Vec<TokenStream> {
     fields
         .args()
@@ -121,7 +252,103 @@ pub(crate) fn generated_ident(template: &ItemStruct, name: &str) -> Ident {
     )
 }
 
-fn read_content(options: &TemplateOptions) -> Result<String, Error> {
+pub(crate) fn slot_ident(name: &str) -> Ident {
+    Ident::new(&format!("__zinal_slot_{}", name), Span::call_site())
+}
+
+struct TemplateSource {
+    path: Option<PathBuf>,
+    content: String,
+    origin: Origin,
+}
+
+/// A template source together with the sources of the templates it extends,
+/// which are read while the template is parsed.
+struct SourceChain {
+    source: TemplateSource,
+    base: OnceCell<Box<SourceChain>>,
+}
+
+impl SourceChain {
+    fn new(source: TemplateSource) -> Self {
+        Self {
+            source,
+            base: OnceCell::new(),
+        }
+    }
+
+    /// The sources starting with the template itself, followed by the
+    /// templates it extends.
+    fn iter(&self) -> impl Iterator<Item = &TemplateSource> {
+        std::iter::successors(Some(self), |chain| chain.base.get().map(Box::as_ref))
+            .map(|chain| &chain.source)
+    }
+}
+
+/// Parse the template and all templates it extends, reading them as they are
+/// referenced, and merge them into a single template.
+fn parse_sources<'src>(
+    sources: &'src SourceChain,
+    options: &TemplateOptions,
+) -> Result<Vec<Item<'src>>, Error> {
+    let mut chain = Vec::new();
+    let mut current = sources;
+
+    loop {
+        let source = &current.source;
+        let items = parser::parse(
+            &source.content,
+            source.origin.clone(),
+            options.parse_options(),
+        )?;
+
+        if let Some(base) = parser::base_template(&items) {
+            let path = template_path(base);
+
+            if sources.iter().any(|s| s.path.as_ref() == Some(&path)) {
+                return Err(base.error(&format!(
+                    "Cyclic template inheritance in `{}`",
+                    path.display()
+                )));
+            }
+
+            let content = std::fs::read_to_string(&path).map_err(|e| {
+                base.error(&format!(
+                    "Could not read template `{}`: {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let base = SourceChain::new(TemplateSource {
+                origin: file_origin(&path, options),
+                path: Some(path),
+                content,
+            });
+
+            if current.base.set(Box::new(base)).is_err() {
+                unreachable!("Every template in the chain is parsed once");
+            }
+        }
+
+        chain.push(items);
+
+        match current.base.get() {
+            Some(base) => current = base,
+            None => break,
+        }
+    }
+
+    let mut items = parser::resolve_inheritance(chain);
+
+    // Only HTML output has contexts like scripts or attributes
+    if matches!(options.escape_mode(), None | Some(EscapeMode::Html)) {
+        parser::assign_contexts(&mut items);
+    }
+
+    Ok(items)
+}
+
+fn read_content(options: &TemplateOptions) -> Result<TemplateSource, Error> {
     if options.path.is_some() {
         read_file_content(options)
     } else {
@@ -129,9 +356,30 @@ fn read_content(options: &TemplateOptions) -> Result<String, Error> {
     }
 }
 
-fn read_file_content(options: &TemplateOptions) -> Result<String, Error> {
-    let file_name = PathBuf::from(options.path.as_ref().expect("Should have been verified"));
+fn read_file_content(options: &TemplateOptions) -> Result<TemplateSource, Error> {
+    let path = template_path(options.path.as_ref().expect("Should have been verified"));
+    let content = read_file(&path)?;
 
+    Ok(TemplateSource {
+        origin: file_origin(&path, options),
+        path: Some(path),
+        content,
+    })
+}
+
+fn read_inline_content(options: &TemplateOptions) -> Result<TemplateSource, Error> {
+    Ok(TemplateSource {
+        path: None,
+        content: options.content.clone().expect("Should have been verified"),
+        origin: options
+            .literal
+            .as_ref()
+            .map(Origin::inline)
+            .unwrap_or_default(),
+    })
+}
+
+fn template_path(file_name: &str) -> PathBuf {
     let mut full_path = PathBuf::from(
         std::env::var("CARGO_MANIFEST_DIR")
             .expect("Internal error: environmental variable `CARGO_MANIFEST_DIR` is not set."),
@@ -139,12 +387,28 @@ fn read_file_content(options: &TemplateOptions) -> Result<String, Error> {
     full_path.push("templates");
     full_path.push(file_name);
 
-    let content =
-        std::fs::read_to_string(full_path).map_err(|e| Error::new(Span::call_site(), e))?;
+    full_path
+}
 
-    Ok(content)
+/// Errors in template files are reported at the path declaration of the template.
+/// Errors the compiler finds in their code note its location in the file, as
+/// the code is expanded from macros named after it.
+fn file_origin(path: &Path, options: &TemplateOptions) -> Origin {
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let name = path.strip_prefix(manifest_dir).unwrap_or(path);
+    let span = options
+        .literal
+        .as_ref()
+        .map_or_else(Span::call_site, LitStr::span);
+
+    Origin::file(name.display().to_string(), span)
 }
 
-fn read_inline_content(options: &TemplateOptions) -> Result<String, Error> {
-    Ok(options.content.clone().expect("Should have been verified"))
+fn read_file(path: &Path) -> Result<String, Error> {
+    std::fs::read_to_string(path).map_err(|e| {
+        Error::new(
+            Span::call_site(),
+            format!("Could not read template `{}`: {}", path.display(), e),
+        )
+    })
 }
@@ -6,8 +6,9 @@ use syn::{
 };
 
 use super::{
-    fields::{TemplateField, TemplateFields},
+    fields::{ContextParam, Source, TemplateField, TemplateFields},
     properties::TemplateProperties,
+    slots::{TemplateSlotDecl, TemplateSlots},
     values::TemplateValues,
 };
 
@@ -18,6 +19,7 @@ pub struct TemplateBuilder<'a> {
     template_ident: &'a Ident,
     template_generics: &'a Generics,
     template_fields: &'a TemplateFields,
+    template_slots: &'a TemplateSlots,
     values: &'a TemplateValues<'a>,
     properties: &'a TemplateProperties,
 }
@@ -26,6 +28,7 @@ impl<'a> TemplateBuilder<'a> {
     pub fn from_template(
         template: &'a ItemStruct,
         fields: &'a TemplateFields,
+        slots: &'a TemplateSlots,
         values: &'a TemplateValues,
         properties: &'a TemplateProperties,
     ) -> Self {
@@ -59,6 +62,7 @@ impl<'a> TemplateBuilder<'a> {
             template_ident: &template.ident,
             template_generics: &template.generics,
             template_fields: fields,
+            template_slots: slots,
             values,
             properties,
         }
@@ -98,6 +102,12 @@ impl<'a> TemplateBuilder<'a> {
             .map(|f| self.generate_setter(f))
             .collect::<Vec<_>>();
 
+        let slot_markers = self
+            .template_slots
+            .all()
+            .map(|s| self.generate_slot_marker(s))
+            .collect::<Vec<_>>();
+
         let build_method = self.generate_build_method();
 
         quote! {
@@ -108,6 +118,8 @@ impl<'a> TemplateBuilder<'a> {
 
                 #(#setters)*
 
+                #(#slot_markers)*
+
                 #build_method
             }
         }
@@ -129,8 +141,25 @@ impl<'a> TemplateBuilder<'a> {
         }
     }
 
+    fn generate_slot_marker(&self, slot: &TemplateSlotDecl) -> TokenStream {
+        let builder_ident = &self.ident;
+        let slot_ident = &slot.ident;
+        let prop = self.properties.prop_ty(slot_ident);
+
+        let builder_args =
+            self.generic_args(parse_quote!(::zinal::builder::WithProperty<#prop, __zinal_Token>));
+
+        quote! {
+            #[doc(hidden)]
+            pub fn #slot_ident(self) -> #builder_ident #builder_args {
+                #builder_ident(self.0.set::<#prop>(|_| {}))
+            }
+        }
+    }
+
     fn generate_build_method(&self) -> TokenStream {
         let template_ident = &self.template_ident;
+        let template_name = template_ident.to_string();
         let (_, template_generics, _) = self.template_generics.split_for_impl();
 
         let mut args = Punctuated::new();
@@ -162,6 +191,16 @@ impl<'a> TemplateBuilder<'a> {
             );
         }
 
+        for slot in self.template_slots.required() {
+            let tail_ident = Ident::new(&format!("SlotTail_{}", slot.name), Span::mixed_site());
+            let prop_ty = self.properties.prop_ty(&slot.ident);
+
+            args.push(parse_quote!(#tail_ident));
+            predicates.push(
+                parse_quote!(__zinal_Token: ::zinal::builder::HasProperty<#prop_ty, #tail_ident>),
+            );
+        }
+
         let build_params = AngleBracketedGenericArguments {
             colon2_token: None,
             lt_token: Default::default(),
@@ -191,15 +230,33 @@ impl<'a> TemplateBuilder<'a> {
             let field_ident = &field.ident;
             let field_name = field_ident.to_string();
 
-            field_initializers.push(quote!(
-                #field_ident: context.get_param().expect(&format!("Missing context parameter {}", #field_name))
-            ));
+            let value = match &field.source {
+                Source::Context(ContextParam::Optional) => quote!(context.get_param()),
+                Source::Context(ContextParam::Default(expr)) => {
+                    quote!(context.get_param().unwrap_or_else(|| #expr))
+                }
+                _ => quote! {
+                    context.get_param().ok_or(::zinal::MissingContextParam {
+                        template: #template_name,
+                        field: #field_name,
+                    })?
+                },
+            };
+
+            field_initializers.push(quote!(#field_ident: #value));
         }
 
         quote! {
-            pub fn build #build_params (self, context: &'__zinal_ctx ::zinal::Context) -> #template_ident #template_generics #where_clause {
-                #template_ident {
+            pub fn try_build #build_params (self, context: &'__zinal_ctx ::zinal::Context) -> ::std::result::Result<#template_ident #template_generics, ::zinal::MissingContextParam> #where_clause {
+                ::std::result::Result::Ok(#template_ident {
                     #(#field_initializers),*
+                })
+            }
+
+            pub fn build #build_params (self, context: &'__zinal_ctx ::zinal::Context) -> #template_ident #template_generics #where_clause {
+                match self.try_build(context) {
+                    ::std::result::Result::Ok(template) => template,
+                    ::std::result::Result::Err(error) => panic!("{}", error),
                 }
             }
         }
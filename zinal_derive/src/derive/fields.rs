@@ -13,7 +13,13 @@ pub struct TemplateField {
 
 pub enum Source {
     Argument,
-    Context,
+    Context(ContextParam),
+}
+
+pub enum ContextParam {
+    Required,
+    Optional,
+    Default(Expr),
 }
 
 pub enum Optionality {
@@ -41,7 +47,7 @@ impl TemplateFields {
             let optionality = parse_optionality(field)?;
             let provides_context = parse_provides_context(field)?;
 
-            if matches!(source, Source::Context) && provides_context {
+            if matches!(source, Source::Context(_)) && provides_context {
                 return Err(Error::new(
                     field.span(),
                     "Field cannot be both #[from_context] and #[provide_context]",
@@ -97,33 +103,53 @@ impl TemplateFields {
         }
 
         fn parse_source(field: &Field) -> Result<Source, Error> {
-            let mut from_context = false;
+            let mut param = None;
             for attr in field.attrs.iter() {
                 if !attr.path().is_ident("from_context") {
                     continue;
                 }
 
-                if from_context {
+                if param.is_some() {
                     return Err(Error::new(
                         attr.span(),
                         "Only one #[from_context] attribute is supported per field",
                     ));
                 }
 
-                if !matches!(attr.meta, Meta::Path(_)) {
-                    return Err(Error::new(
-                        attr.span(),
-                        "#[from_context] attribute does not support arguments",
-                    ));
+                if matches!(attr.meta, Meta::Path(_)) {
+                    param = Some(match is_option(&field.ty) {
+                        true => ContextParam::Optional,
+                        false => ContextParam::Required,
+                    });
+                } else {
+                    attr.parse_nested_meta(|meta| {
+                        if meta.path.is_ident("default") {
+                            let value = meta.value()?;
+                            let expr: Expr = value.parse()?;
+
+                            param = Some(ContextParam::Default(expr));
+                            Ok(())
+                        } else {
+                            Err(meta.error("Unsupported attribute. Expected #[from_context] or #[from_context(default = ...)]"))
+                        }
+                    })?;
                 }
+            }
 
-                from_context = true;
+            match param {
+                Some(param) => Ok(Source::Context(param)),
+                None => Ok(Source::Argument),
             }
+        }
 
-            if from_context {
-                Ok(Source::Context)
-            } else {
-                Ok(Source::Argument)
+        fn is_option(ty: &Type) -> bool {
+            match ty {
+                Type::Path(path) => path
+                    .path
+                    .segments
+                    .last()
+                    .is_some_and(|segment| segment.ident == "Option"),
+                _ => false,
             }
         }
 
@@ -168,7 +194,7 @@ impl TemplateFields {
     pub fn ctx(&self) -> impl Iterator<Item = &TemplateField> {
         self.0
             .iter()
-            .filter(|f| matches!(f.source, Source::Context))
+            .filter(|f| matches!(f.source, Source::Context(_)))
     }
 }
 
@@ -0,0 +1,89 @@
+use std::collections::BTreeSet;
+
+use proc_macro2::{Ident, TokenStream};
+use quote::ToTokens;
+use syn::ItemStruct;
+
+use crate::{
+    emit,
+    parser::{Item, TemplateArgumentValue, TemplateSlot},
+};
+
+/// The built-in filters used by a template. They are imported where the
+/// template is declared, so that functions of the same name take precedence.
+pub struct TemplateFilters {
+    mod_ident: Ident,
+    names: BTreeSet<&'static str>,
+}
+
+impl TemplateFilters {
+    pub fn from_items(template: &ItemStruct, items: &[Item<'_>]) -> Self {
+        let mut names = BTreeSet::new();
+        collect_filters(items, &mut names);
+
+        return Self {
+            mod_ident: super::generated_ident(template, "filters"),
+            names,
+        };
+
+        fn collect_filters(items: &[Item<'_>], names: &mut BTreeSet<&'static str>) {
+            for item in items {
+                match item {
+                    Item::Expression(code, _) | Item::UnescapedExpression(code) => {
+                        names.extend(emit::builtin_filters(code));
+                    }
+                    Item::KeywordStatement { body, .. } | Item::Block { body, .. } => {
+                        collect_filters(body, names)
+                    }
+                    Item::ChildTemplate {
+                        arguments,
+                        children,
+                        slots,
+                        ..
+                    } => {
+                        for argument in arguments {
+                            if let TemplateArgumentValue::Expression(code) = &argument.value {
+                                names.extend(emit::builtin_filters(code));
+                            }
+                        }
+                        collect_filters(children, names);
+                        for TemplateSlot { children, .. } in slots {
+                            collect_filters(children, names);
+                        }
+                    }
+                    Item::Literal(_)
+                    | Item::PlainStatement(_)
+                    | Item::Slot { .. }
+                    | Item::Extends(_) => {}
+                }
+            }
+        }
+    }
+}
+
+impl ToTokens for TemplateFilters {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        if self.names.is_empty() {
+            return;
+        }
+
+        let mod_ident = &self.mod_ident;
+        let names = self
+            .names
+            .iter()
+            .map(|name| Ident::new(name, mod_ident.span()));
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            mod #mod_ident {
+                #[allow(unused_imports)]
+                pub use ::zinal::filters::builtin::{#(#names),*};
+            }
+
+            #[allow(unused_imports)]
+            use #mod_ident::*;
+        }
+        .to_tokens(tokens);
+    }
+}
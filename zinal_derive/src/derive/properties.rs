@@ -2,7 +2,7 @@ use proc_macro2::{Ident, TokenStream};
 use quote::ToTokens;
 use syn::{ItemStruct, Type};
 
-use super::fields::TemplateFields;
+use super::{fields::TemplateFields, slots::TemplateSlots};
 
 pub struct TemplateProperties {
     pub mod_ident: Ident,
@@ -10,10 +10,18 @@ pub struct TemplateProperties {
 }
 
 impl TemplateProperties {
-    pub fn from_template(template: &ItemStruct, fields: &TemplateFields) -> Self {
+    pub fn from_template(
+        template: &ItemStruct,
+        fields: &TemplateFields,
+        slots: &TemplateSlots,
+    ) -> Self {
         Self {
             mod_ident: super::generated_ident(template, "Properties"),
-            properties: fields.args().map(|f| f.ident.clone()).collect(),
+            properties: fields
+                .args()
+                .map(|f| f.ident.clone())
+                .chain(slots.all().map(|s| s.ident.clone()))
+                .collect(),
         }
     }
 
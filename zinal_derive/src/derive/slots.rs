@@ -0,0 +1,64 @@
+use proc_macro2::Ident;
+
+use crate::parser::{Item, TemplateSlot};
+
+pub struct TemplateSlots(Vec<TemplateSlotDecl>);
+
+pub struct TemplateSlotDecl {
+    pub ident: Ident,
+    pub name: String,
+    pub optional: bool,
+}
+
+impl TemplateSlots {
+    pub fn from_items(items: &[Item<'_>]) -> Self {
+        let mut slots = Vec::new();
+        collect_slots(items, &mut slots);
+
+        return Self(slots);
+
+        fn collect_slots(items: &[Item<'_>], slots: &mut Vec<TemplateSlotDecl>) {
+            for item in items {
+                match item {
+                    Item::Slot { name, optional } => {
+                        match slots.iter_mut().find(|s| s.name == name.as_ref()) {
+                            // A slot is required if it is required in at least one place
+                            Some(slot) => slot.optional &= optional,
+                            None => slots.push(TemplateSlotDecl {
+                                ident: super::slot_ident(name),
+                                name: name.to_string(),
+                                optional: *optional,
+                            }),
+                        }
+                    }
+                    Item::KeywordStatement { body, .. } | Item::Block { body, .. } => {
+                        collect_slots(body, slots)
+                    }
+                    Item::ChildTemplate {
+                        children,
+                        slots: child_slots,
+                        ..
+                    } => {
+                        collect_slots(children, slots);
+                        for TemplateSlot { children, .. } in child_slots {
+                            collect_slots(children, slots);
+                        }
+                    }
+                    Item::Literal(_)
+                    | Item::Expression(..)
+                    | Item::UnescapedExpression(_)
+                    | Item::PlainStatement(_)
+                    | Item::Extends(_) => {}
+                }
+            }
+        }
+    }
+
+    pub fn all(&self) -> impl Iterator<Item = &TemplateSlotDecl> {
+        self.0.iter()
+    }
+
+    pub fn required(&self) -> impl Iterator<Item = &TemplateSlotDecl> {
+        self.0.iter().filter(|s| !s.optional)
+    }
+}
@@ -1,8 +1,15 @@
-use proc_macro2::TokenStream;
+use proc_macro2::{Span, TokenStream};
 use quote::ToTokens;
 use syn::Error;
 
-use crate::parser::{Item, Keyword, TemplateArgument, TemplateArgumentValue};
+use crate::{
+    derive::slot_ident,
+    parser::{
+        Code, EscapeContext, Item, Keyword, TemplateArgument, TemplateArgumentValue, TemplateSlot,
+    },
+};
+
+mod format;
 
 trait Emit {
     fn emit(self) -> Result<TokenStream, Error>;
@@ -19,80 +26,164 @@ impl Item<'_> {
 impl Emit for Item<'_> {
     fn emit(self) -> Result<TokenStream, Error> {
         match self {
+            // Literals can become empty by trimming whitespace
+            Item::Literal(s) if s.is_empty() => Ok(TokenStream::new()),
+
             Item::Literal(s) => Ok(quote! {
                 write!(__zinal_writer, "{}", #s)?;
             }),
 
-            Item::Expression(expr) => {
-                let expr = expr.as_ref();
-                if expr == "@children" {
+            Item::Expression(expr, context) => {
+                if expr.as_ref() == "@children" {
                     Ok(quote! {
                         ::zinal::Children::render(&__zinal_children, __zinal_writer, __zinal_escaper, __zinal_context)?;
                     })
                 } else {
-                    let expr = syn::parse_str::<syn::Expr>(expr)?;
+                    let location = emit_location(&expr);
+                    let (macro_def, value) = emit_value(expr)?;
+                    let render = emit_render(value, context);
                     Ok(quote! {
-                        ::zinal::Renderable::render(&#expr, __zinal_writer, __zinal_escaper)?;
+                        #location
+                        #macro_def
+                        #render
                     })
                 }
             }
 
+            Item::UnescapedExpression(expr) => {
+                let location = emit_location(&expr);
+                let (macro_def, value) = emit_value(expr)?;
+                Ok(quote! {
+                    #location
+                    #macro_def
+                    ::zinal::Renderable::render(&::zinal::PreEscaped(&#value), __zinal_writer, __zinal_escaper)?;
+                })
+            }
+
             Item::KeywordStatement {
                 keyword,
+                label,
                 statement,
                 body,
             } => {
+                // Else branches and match arms can't be preceded by a statement
+                let location = match &statement {
+                    Some(s) if !matches!(keyword, Keyword::ElseIf | Keyword::Case) => {
+                        emit_location(s)
+                    }
+                    _ => TokenStream::new(),
+                };
                 let statement = match statement {
-                    Some(s) => Some(syn::parse_str::<TokenStream>(s.as_ref())?),
+                    Some(s) => Some(s.parse::<TokenStream>()?),
+                    None => None,
+                };
+
+                if keyword == Keyword::End {
+                    return Err(Error::new(Span::call_site(), "Unexpected end statement"));
+                }
+
+                if !keyword.has_body() {
+                    // let, break and continue are plain statements, a let binding
+                    // stays in scope for the rest of the enclosing block
+                    return Ok(quote! {
+                        #location
+                        #keyword #statement;
+                    });
+                }
+
+                let label = match label {
+                    Some(l) => {
+                        let lifetime = syn::parse_str::<syn::Lifetime>(l.as_ref())?;
+                        Some(quote!(#lifetime:))
+                    }
                     None => None,
                 };
                 let body = Item::emit_all(body)?;
 
+                if keyword == Keyword::Case {
+                    return Ok(quote! {
+                        #statement => {
+                            #(#body)*
+                        }
+                    });
+                }
+
                 Ok(quote! {
-                    #keyword #statement {
+                    #location
+                    #label #keyword #statement {
                         #(#body)*
                     }
                 })
             }
 
-            Item::PlainStatement(tokens) => Ok(quote! {
-                #tokens;
-            }),
+            Item::PlainStatement(statement) => {
+                let location = emit_location(&statement);
+                let statement = statement.parse::<TokenStream>()?;
+                Ok(quote! {
+                    #location
+                    #statement;
+                })
+            }
 
             Item::ChildTemplate {
                 name,
                 arguments,
                 children,
+                slots,
             } => {
                 let ty = syn::parse_str::<syn::TypePath>(name.as_ref())?;
-                let arguments = arguments
+                let location = arguments
+                    .iter()
+                    .find_map(|a| match &a.value {
+                        TemplateArgumentValue::Expression(code) => Some(emit_location(code)),
+                        _ => None,
+                    })
+                    .unwrap_or_default();
+                let (macro_defs, arguments) = arguments
                     .into_iter()
-                    .map(Emit::emit)
-                    .collect::<Result<Vec<_>, _>>()?;
+                    .map(TemplateArgument::emit_located)
+                    .collect::<Result<(Vec<_>, Vec<_>), _>>()?;
+                let slot_markers = slots
+                    .iter()
+                    .map(|s| {
+                        let ident = slot_ident(&s.name);
+                        quote!(.#ident())
+                    })
+                    .collect::<Vec<_>>();
 
                 let children = if !children.is_empty() {
-                    let children = Item::emit_all(children)?;
-                    quote! {
-                        |
-                            __zinal_writer: &mut dyn ::std::fmt::Write,
-                            __zinal_escaper: &dyn ::zinal::Escaper,
-                            __zinal_context: &::zinal::Context
-                        | {
-                            #(#children)*
-                            Ok(())
-                        }
-                    }
+                    emit_children_closure(children)?
                 } else {
                     quote!(::zinal::EmptyChildren)
                 };
 
+                let children = if !slots.is_empty() {
+                    let slots = slots
+                        .into_iter()
+                        .map(Emit::emit)
+                        .collect::<Result<Vec<_>, _>>()?;
+                    quote!(::zinal::Slots::new(#children, [#(#slots),*]))
+                } else {
+                    children
+                };
+
                 let template = quote! {
-                    #ty::builder() #(#arguments)* .build(__zinal_context)
+                    #ty::builder() #(#arguments)* #(#slot_markers)* .try_build(__zinal_context)?
+                };
+
+                // Output preceding the child template can be sent when streaming
+                let flush = if cfg!(feature = "stream") {
+                    quote!(::zinal::Context::flush(__zinal_context)?;)
+                } else {
+                    TokenStream::new()
                 };
 
                 Ok(quote! {
                     {
+                        #location
+                        #(#macro_defs)*
                         let __zinal_template = #template;
+                        #flush
 
                         ::zinal::Template::render(
                             __zinal_template,
@@ -103,10 +194,281 @@ impl Emit for Item<'_> {
                     }
                 })
             }
+
+            Item::Block { body, .. } => {
+                let body = Item::emit_all(body)?;
+                Ok(quote!(#(#body)*))
+            }
+
+            Item::Extends(path) => {
+                Err(path.error("`extends` must be used at the top level of a template"))
+            }
+
+            Item::Slot { name, .. } => {
+                let name = name.as_ref();
+                Ok(quote! {
+                    ::zinal::Children::render_slot(&__zinal_children, #name, __zinal_writer, __zinal_escaper, __zinal_context)?;
+                })
+            }
+        }
+    }
+}
+
+/// Children belong to the template declaring them, so they are escaped with
+/// its escaper instead of the one of the child template, and errors are
+/// located in the declaring template.
+fn emit_children_closure(children: Vec<Item<'_>>) -> Result<TokenStream, Error> {
+    let children = Item::emit_all(children)?;
+    Ok(quote! {
+        |
+            __zinal_writer: &mut dyn ::std::fmt::Write,
+            _: &dyn ::zinal::Escaper,
+            __zinal_context: &::zinal::Context
+        | {
+            (|| -> ::std::result::Result<(), ::zinal::RenderError> {
+                #(#children)*
+                Ok(())
+            })()
+            .map_err(|e| e.at(__zinal_location.get()))
+        }
+    })
+}
+
+/// Emit recording the location of the code, so that errors occurring while
+/// it is executed can be located in the template.
+fn emit_location(code: &Code<'_>) -> TokenStream {
+    let Some((file, line, column)) = code.location() else {
+        return TokenStream::new();
+    };
+
+    let file = match file {
+        Some(file) => quote!(::std::option::Option::Some(#file)),
+        None => quote!(::std::option::Option::None),
+    };
+    let line = proc_macro2::Literal::usize_unsuffixed(line);
+    let column = proc_macro2::Literal::usize_unsuffixed(column);
+
+    quote! {
+        __zinal_location.set(::std::option::Option::Some(::zinal::Location {
+            file: #file,
+            line: #line,
+            column: #column,
+        }));
+    }
+}
+
+/// Emit rendering a value in the given context. Expressions in HTML content
+/// use the escaper the template is rendered with.
+fn emit_render(value: TokenStream, context: EscapeContext) -> TokenStream {
+    let escaper = match context {
+        EscapeContext::Html => quote!(__zinal_escaper),
+        EscapeContext::Attribute => quote!(&::zinal::html::UnquotedAttributeEscaper),
+        EscapeContext::ScriptString => quote!(&::zinal::html::ScriptStringEscaper),
+        EscapeContext::Style => quote!(&::zinal::html::StyleEscaper),
+        EscapeContext::UrlPart => quote!(&::zinal::html::UrlPartEscaper),
+
+        // The string literal is opened and closed once for the whole value
+        EscapeContext::Script => {
+            return quote! {
+                {
+                    let __zinal_script = ::zinal::html::ScriptEscaper::new();
+                    ::zinal::Renderable::render(&#value, __zinal_writer, &__zinal_script)?;
+                    __zinal_script.finish(__zinal_writer)?;
+                }
+            };
+        }
+
+        // The scheme can only be checked on the whole URL
+        EscapeContext::Url => {
+            return quote! {
+                {
+                    let mut __zinal_url = ::std::string::String::new();
+                    ::zinal::Renderable::render(&#value, &mut __zinal_url, &::zinal::NoEscaper)?;
+                    ::zinal::Escaper::escape(&::zinal::html::UrlEscaper, &__zinal_url, __zinal_writer)?;
+                }
+            };
         }
+
+        // Attribute values are decoded before they are interpreted as script or style
+        EscapeContext::EventHandler
+        | EscapeContext::EventHandlerString
+        | EscapeContext::StyleAttribute => {
+            let inner = match context {
+                EscapeContext::EventHandler => EscapeContext::Script,
+                EscapeContext::EventHandlerString => EscapeContext::ScriptString,
+                _ => EscapeContext::Style,
+            };
+            let render = emit_render(value, inner);
+
+            return quote! {
+                {
+                    let __zinal_writer = &mut ::zinal::EscapingWriter::new(
+                        &::zinal::html::UnquotedAttributeEscaper,
+                        __zinal_writer,
+                    );
+                    #render
+                }
+            };
+        }
+    };
+
+    quote! {
+        ::zinal::Renderable::render(&#value, __zinal_writer, #escaper)?;
     }
 }
 
+/// Emit code from a template file as the invocation of a macro named after its
+/// location, together with the definition of the macro.
+///
+/// The compiler can only point errors in template files to the path of the
+/// template, but it notes the macro they originate in, like ``this error
+/// originates in the macro `templates_page_html_line_3_column_5` ``. Code
+/// from inline templates is emitted as is, together with an empty definition.
+fn emit_located(code: &Code<'_>, tokens: TokenStream) -> (TokenStream, TokenStream) {
+    let Some((Some(file), line, column)) = code.location() else {
+        return (TokenStream::new(), tokens);
+    };
+
+    let file: String = file
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let name = format_ident!("{}_line_{}_column_{}", file.trim_matches('_'), line, column);
+
+    (
+        quote! {
+            macro_rules! #name {
+                () => { #tokens };
+            }
+        },
+        quote!(#name!()),
+    )
+}
+
+/// Emit the value of a rendered expression, formatted with its format spec if
+/// it has one, together with the definition of the macro it is located with.
+fn emit_value(expr: Code<'_>) -> Result<(TokenStream, TokenStream), Error> {
+    let (expr, spec) = format::split_format_spec(expr);
+    let span = expr.span();
+    let (macro_def, value) = emit_located(&expr, emit_expression(expr.parse::<syn::Expr>()?));
+
+    Ok(match spec {
+        Some(spec) => {
+            let format = syn::LitStr::new(&format!("{{:{}}}", spec), span);
+            (macro_def, quote!(::std::format_args!(#format, #value)))
+        }
+        None => (macro_def, value),
+    })
+}
+
+/// The filters from `zinal::filters` that can be used without parentheses.
+const BUILTIN_FILTERS: &[&str] = &[
+    "default",
+    "join",
+    "json",
+    "lower",
+    "pluralize",
+    "trim",
+    "truncate",
+    "upper",
+    "urlencode",
+];
+
+/// Emit an expression, applying the filters it ends with.
+///
+/// Filters are written as `value | filter | filter(args)` and are plain
+/// functions, called with a reference to the value and the given arguments.
+/// Filters are resolved where the template is declared, where the built-in
+/// filters the template uses are imported as well. Only calls of lower case
+/// functions and the names of built-in filters are taken for filters, other
+/// operands like `MASK` or `b` are bitwise or expressions.
+fn emit_expression(expr: syn::Expr) -> TokenStream {
+    let (value, filters) = split_filters(expr);
+
+    if filters.is_empty() {
+        // The expression is emitted as `&#expr`, so operators binding weaker
+        // than the reference need to be parenthesized.
+        return match value {
+            syn::Expr::Binary(_)
+            | syn::Expr::Cast(_)
+            | syn::Expr::Range(_)
+            | syn::Expr::Assign(_)
+            | syn::Expr::Closure(_) => quote!((#value)),
+            value => value.into_token_stream(),
+        };
+    }
+
+    filters.into_iter().fold(
+        value.into_token_stream(),
+        |value, (filter, args)| quote!(#filter(&(#value) #(, #args)*)),
+    )
+}
+
+/// Split an expression into the value and the filters applied to it, in the
+/// order they are applied.
+fn split_filters(expr: syn::Expr) -> (syn::Expr, Vec<(syn::ExprPath, Vec<syn::Expr>)>) {
+    let mut value = expr;
+    let mut filters = Vec::new();
+
+    while let syn::Expr::Binary(syn::ExprBinary {
+        left,
+        op: syn::BinOp::BitOr(_),
+        right,
+        ..
+    }) = &value
+    {
+        let filter = match right.as_ref() {
+            syn::Expr::Path(path) if builtin_filter(path).is_some() => (path.clone(), Vec::new()),
+            syn::Expr::Call(syn::ExprCall { func, args, .. }) => match func.as_ref() {
+                syn::Expr::Path(path) if is_function_name(path) => {
+                    (path.clone(), args.iter().cloned().collect())
+                }
+                _ => break,
+            },
+            _ => break,
+        };
+
+        filters.push(filter);
+        value = left.as_ref().clone();
+    }
+
+    filters.reverse();
+    (value, filters)
+}
+
+/// The built-in filters applied in the expression, which need to be imported
+/// where the template is declared.
+pub(crate) fn builtin_filters(expr: &Code<'_>) -> Vec<&'static str> {
+    let (expr, _) = format::split_format_spec(expr.clone());
+    let Ok(expr) = syn::parse_str::<syn::Expr>(&expr) else {
+        return Vec::new();
+    };
+
+    split_filters(expr)
+        .1
+        .iter()
+        .filter_map(|(path, _)| builtin_filter(path))
+        .collect()
+}
+
+/// The name of the built-in filter the path refers to, if any.
+fn builtin_filter(path: &syn::ExprPath) -> Option<&'static str> {
+    let ident = path.path.get_ident()?.to_string();
+    BUILTIN_FILTERS.iter().copied().find(|name| *name == ident)
+}
+
+/// Whether the path names a function, which are written in lower case, and
+/// not a constant or type.
+fn is_function_name(path: &syn::ExprPath) -> bool {
+    path.path.segments.last().is_some_and(|segment| {
+        segment
+            .ident
+            .to_string()
+            .trim_start_matches("r#")
+            .starts_with(|c: char| c.is_lowercase() || c == '_')
+    })
+}
+
 impl ToTokens for Keyword {
     fn to_tokens(&self, tokens: &mut TokenStream) {
         let keyword = match self {
@@ -119,20 +481,45 @@ impl ToTokens for Keyword {
             Keyword::Break => quote!(break),
             Keyword::Continue => quote!(continue),
             Keyword::Let => quote!(let),
+            Keyword::Match => quote!(match),
 
-            Keyword::End => unreachable!(),
+            Keyword::End | Keyword::Case => unreachable!(),
         };
         keyword.to_tokens(tokens);
     }
 }
 
-impl Emit for TemplateArgument<'_> {
-    fn emit(self) -> Result<TokenStream, Error> {
+impl TemplateArgument<'_> {
+    /// Emit the argument, together with the definition of the macro its value
+    /// is located with.
+    fn emit_located(self) -> Result<(TokenStream, TokenStream), Error> {
         let name = syn::parse_str::<syn::Ident>(&format!("r#{}", self.name))?;
+        let code = match &self.value {
+            TemplateArgumentValue::Expression(code) => Some(code.clone()),
+            _ => None,
+        };
         let value = self.value.emit()?;
+        let (macro_def, value) = match code {
+            Some(code) => emit_located(&code, value),
+            None => (TokenStream::new(), value),
+        };
+
+        Ok((
+            macro_def,
+            quote! {
+                .#name(#value)
+            },
+        ))
+    }
+}
+
+impl Emit for TemplateSlot<'_> {
+    fn emit(self) -> Result<TokenStream, Error> {
+        let name = self.name.as_ref();
+        let children = emit_children_closure(self.children)?;
 
         Ok(quote! {
-            .#name(#value)
+            ::zinal::Slot::new(#name, &#children)
         })
     }
 }
@@ -142,21 +529,25 @@ impl Emit for TemplateArgumentValue<'_> {
         Ok(match self {
             TemplateArgumentValue::StrLiteral(v) => quote!(#v.into()),
             TemplateArgumentValue::BoolLiteral(v) => quote!(#v.into()),
-            TemplateArgumentValue::Expression(expr) => {
-                syn::parse_str::<TokenStream>(expr.as_ref())?
-            }
+            TemplateArgumentValue::Expression(expr) => match expr.parse::<syn::Expr>() {
+                Ok(expr) => emit_expression(expr),
+                Err(_) => expr.parse::<TokenStream>()?,
+            },
         })
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::borrow::Cow;
+    use std::{borrow::Cow, rc::Rc};
 
-    use proc_macro2::TokenStream;
+    use proc_macro2::{Span, TokenStream};
     use syn::Error;
 
-    use crate::parser::{Item, Keyword, TemplateArgument, TemplateArgumentValue};
+    use crate::parser::{
+        Code, EscapeContext, Item, Keyword, Origin, TemplateArgument, TemplateArgumentValue,
+        TemplateSlot,
+    };
 
     #[test]
     fn literal() {
@@ -173,7 +564,10 @@ mod tests {
 
     #[test]
     fn expression() {
-        let items = vec![Item::Expression(Cow::from("self.name.to_upper()"))];
+        let items = vec![Item::Expression(
+            Code::from("self.name.to_upper()"),
+            EscapeContext::Html,
+        )];
 
         let tokens = Item::emit_all(items);
 
@@ -184,11 +578,120 @@ mod tests {
         assert_text(tokens, expected);
     }
 
+    #[test]
+    fn expression_in_template_file() {
+        let source = "<p>\n  {{ self.name }}</p>";
+        let origin = Rc::new(Origin::file(
+            String::from("templates/page.html"),
+            Span::call_site(),
+        ));
+        let items = vec![Item::Expression(
+            Code::new(Cow::from("self.name"), source, 9, origin),
+            EscapeContext::Html,
+        )];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            __zinal_location.set(::std::option::Option::Some(::zinal::Location {
+                file: ::std::option::Option::Some("templates/page.html"),
+                line: 2,
+                column: 6,
+            }));
+            macro_rules! templates_page_html_line_2_column_6 {
+                () => { self.name };
+            }
+            ::zinal::Renderable::render(&templates_page_html_line_2_column_6!(), __zinal_writer, __zinal_escaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn expression_with_filters() {
+        let items = vec![Item::Expression(
+            Code::from("self.title | upper | truncate(40)"),
+            EscapeContext::Html,
+        )];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            ::zinal::Renderable::render(&truncate(&(upper(&(self.title))), 40), __zinal_writer, __zinal_escaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn expression_with_format_spec() {
+        let items = vec![Item::Expression(
+            Code::from("self.price:>8.2"),
+            EscapeContext::Html,
+        )];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            ::zinal::Renderable::render(&::std::format_args!("{:>8.2}", self.price), __zinal_writer, __zinal_escaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn expression_in_script() {
+        let items = vec![Item::Expression(
+            Code::from("self.name"),
+            EscapeContext::ScriptString,
+        )];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            ::zinal::Renderable::render(&self.name, __zinal_writer, &::zinal::html::ScriptStringEscaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn unescaped_expression() {
+        let items = vec![Item::UnescapedExpression(Code::from("self.html"))];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            ::zinal::Renderable::render(&::zinal::PreEscaped(&self.html), __zinal_writer, __zinal_escaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn expression_with_bitwise_or() {
+        let items = vec![
+            Item::Expression(Code::from("self.flags | 0x10"), EscapeContext::Html),
+            Item::Expression(Code::from("self.flags | MASK | hex()"), EscapeContext::Html),
+            Item::Expression(Code::from("a | b"), EscapeContext::Html),
+        ];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            ::zinal::Renderable::render(&(self.flags | 0x10), __zinal_writer, __zinal_escaper)?;
+            ::zinal::Renderable::render(&hex(&(self.flags | MASK)), __zinal_writer, __zinal_escaper)?;
+            ::zinal::Renderable::render(&(a | b), __zinal_writer, __zinal_escaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
     #[test]
     fn combination() {
         let items = vec![
             Item::Literal(Cow::from("Hello, ")),
-            Item::Expression(Cow::from("self.name.to_upper()")),
+            Item::Expression(Code::from("self.name.to_upper()"), EscapeContext::Html),
             Item::Literal(Cow::from("!")),
         ];
 
@@ -207,7 +710,8 @@ mod tests {
     fn keyword_statement_if() {
         let items = vec![Item::KeywordStatement {
             keyword: Keyword::If,
-            statement: Some(Cow::from("self.age > 18")),
+            label: None,
+            statement: Some(Code::from("self.age > 18")),
             body: vec![Item::Literal(Cow::from("Hello, World!"))],
         }];
 
@@ -226,6 +730,7 @@ mod tests {
     fn keyword_statement_loop() {
         let items = vec![Item::KeywordStatement {
             keyword: Keyword::Loop,
+            label: None,
             statement: None,
             body: vec![Item::Literal(Cow::from("Hello, World!"))],
         }];
@@ -245,7 +750,8 @@ mod tests {
     fn keyword_statement_for() {
         let items = vec![Item::KeywordStatement {
             keyword: Keyword::For,
-            statement: Some(Cow::from("name in self.names")),
+            label: None,
+            statement: Some(Code::from("name in self.names")),
             body: vec![Item::Literal(Cow::from("Hello, World!"))],
         }];
 
@@ -260,6 +766,131 @@ mod tests {
         assert_text(tokens, expected);
     }
 
+    #[test]
+    fn keyword_statement_labeled_loop() {
+        let items = vec![Item::KeywordStatement {
+            keyword: Keyword::For,
+            label: Some(Cow::from("'outer")),
+            statement: Some(Code::from("row in &self.rows")),
+            body: vec![Item::KeywordStatement {
+                keyword: Keyword::Break,
+                label: None,
+                statement: Some(Code::from("'outer")),
+                body: vec![],
+            }],
+        }];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            'outer: for row in &self.rows {
+                break 'outer;
+            }
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn plain_statement() {
+        let items = vec![Item::PlainStatement(Code::from("let x = 42"))];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            let x = 42;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn keyword_statement_let() {
+        let items = vec![
+            Item::KeywordStatement {
+                keyword: Keyword::Let,
+                label: None,
+                statement: Some(Code::from("x = compute()")),
+                body: vec![],
+            },
+            Item::Expression(Code::from("x"), EscapeContext::Html),
+        ];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            let x = compute();
+            ::zinal::Renderable::render(&x, __zinal_writer, __zinal_escaper)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn keyword_statement_break_continue() {
+        let items = vec![
+            Item::KeywordStatement {
+                keyword: Keyword::Continue,
+                label: None,
+                statement: None,
+                body: vec![],
+            },
+            Item::KeywordStatement {
+                keyword: Keyword::Break,
+                label: None,
+                statement: None,
+                body: vec![],
+            },
+        ];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            continue;
+            break;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn keyword_statement_match() {
+        let items = vec![Item::KeywordStatement {
+            keyword: Keyword::Match,
+            label: None,
+            statement: Some(Code::from("self.state")),
+            body: vec![
+                Item::KeywordStatement {
+                    keyword: Keyword::Case,
+                    label: None,
+                    statement: Some(Code::from("State::Loading")),
+                    body: vec![Item::Literal(Cow::from("Loading"))],
+                },
+                Item::KeywordStatement {
+                    keyword: Keyword::Case,
+                    label: None,
+                    statement: Some(Code::from("State::Done(n) if n > 0")),
+                    body: vec![Item::Expression(Code::from("n"), EscapeContext::Html)],
+                },
+            ],
+        }];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            match self.state {
+                State::Loading => {
+                    write!(__zinal_writer, "{}", "Loading")?;
+                }
+                State::Done(n) if n > 0 => {
+                    ::zinal::Renderable::render(&n, __zinal_writer, __zinal_escaper)?;
+                }
+            }
+        };
+
+        assert_text(tokens, expected);
+    }
+
     #[test]
     fn child_template() {
         let items = vec![Item::ChildTemplate {
@@ -267,7 +898,7 @@ mod tests {
             arguments: vec![
                 TemplateArgument {
                     name: Cow::from("expr"),
-                    value: TemplateArgumentValue::Expression(Cow::from("self.name")),
+                    value: TemplateArgumentValue::Expression(Code::from("self.name")),
                 },
                 TemplateArgument {
                     name: Cow::from("str_lit"),
@@ -283,9 +914,11 @@ mod tests {
                 },
             ],
             children: vec![],
+            slots: vec![],
         }];
 
         let tokens = Item::emit_all(items);
+        let flush = expected_flush();
 
         let expected = quote! {
             {
@@ -294,7 +927,8 @@ mod tests {
                     .r#str_lit("Literal".into())
                     .r#bool_lit_true(true.into())
                     .r#bool_lit_false(false.into())
-                    .build(__zinal_context);
+                    .try_build(__zinal_context)?;
+                #flush
                 ::zinal::Template::render(__zinal_template, __zinal_writer, __zinal_escaper, __zinal_context, ::zinal::EmptyChildren)?;
             }
         };
@@ -302,6 +936,92 @@ mod tests {
         assert_text(tokens, expected);
     }
 
+    #[test]
+    fn child_template_with_slots() {
+        let items = vec![Item::ChildTemplate {
+            name: Cow::from("Card"),
+            arguments: vec![],
+            children: vec![],
+            slots: vec![TemplateSlot {
+                name: Cow::from("header"),
+                children: vec![Item::Literal(Cow::from("Title"))],
+            }],
+        }];
+
+        let tokens = Item::emit_all(items);
+        let flush = expected_flush();
+
+        let expected = quote! {
+            {
+                let __zinal_template = Card::builder()
+                    .__zinal_slot_header()
+                    .try_build(__zinal_context)?;
+                #flush
+                ::zinal::Template::render(
+                    __zinal_template,
+                    __zinal_writer,
+                    __zinal_escaper,
+                    __zinal_context,
+                    ::zinal::Slots::new(::zinal::EmptyChildren, [
+                        ::zinal::Slot::new("header", &|
+                            __zinal_writer: &mut dyn ::std::fmt::Write,
+                            _: &dyn ::zinal::Escaper,
+                            __zinal_context: &::zinal::Context
+                        | {
+                            (|| -> ::std::result::Result<(), ::zinal::RenderError> {
+                                write!(__zinal_writer, "{}", "Title")?;
+                                Ok(())
+                            })()
+                            .map_err(|e| e.at(__zinal_location.get()))
+                        })
+                    ]))?;
+            }
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn block() {
+        let items = vec![Item::Block {
+            name: Cow::from("title"),
+            body: vec![Item::Literal(Cow::from("Hello, World!"))],
+        }];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            write!(__zinal_writer, "{}", "Hello, World!")?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    #[test]
+    fn slot() {
+        let items = vec![Item::Slot {
+            name: Cow::from("header"),
+            optional: false,
+        }];
+
+        let tokens = Item::emit_all(items);
+
+        let expected = quote! {
+            ::zinal::Children::render_slot(&__zinal_children, "header", __zinal_writer, __zinal_escaper, __zinal_context)?;
+        };
+
+        assert_text(tokens, expected);
+    }
+
+    /// Child templates flush streamed output when the stream feature is enabled.
+    fn expected_flush() -> TokenStream {
+        if cfg!(feature = "stream") {
+            quote!(::zinal::Context::flush(__zinal_context)?;)
+        } else {
+            TokenStream::new()
+        }
+    }
+
     fn assert_text(tokens: Result<Vec<TokenStream>, Error>, expected: TokenStream) {
         let expected: String = expected.to_string();
 
@@ -0,0 +1,207 @@
+use std::borrow::Cow;
+
+use crate::parser::Code;
+
+/// Split a trailing format spec like in `{{ value:.2 }}` from the expression.
+///
+/// The spec follows the last colon outside of any brackets, strings or
+/// character literals, as long as it is not part of a path separator and the
+/// remainder is a valid format spec.
+pub fn split_format_spec(code: Code<'_>) -> (Code<'_>, Option<String>) {
+    let Some(colon) = find_last_colon(&code) else {
+        return (code, None);
+    };
+
+    let spec = code[colon + 1..].trim();
+    if !is_format_spec(spec) {
+        return (code, None);
+    }
+
+    let spec = spec.to_owned();
+    let code = code.map(|text| match text {
+        Cow::Borrowed(text) => Cow::Borrowed(&text[..colon]),
+        Cow::Owned(mut text) => {
+            text.truncate(colon);
+            Cow::Owned(text)
+        }
+    });
+
+    (code, Some(spec))
+}
+
+fn find_last_colon(code: &str) -> Option<usize> {
+    let mut last = None;
+    let mut depth = 0usize;
+    let mut chars = code.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            '"' => skip_string(&mut chars),
+            '\'' => skip_char(code, index, &mut chars),
+            ':' => {
+                if chars.next_if(|(_, c)| *c == ':').is_some() {
+                    continue;
+                }
+                if depth == 0 && !code[..index].ends_with(':') {
+                    last = Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    last
+}
+
+fn skip_string(chars: &mut impl Iterator<Item = (usize, char)>) {
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '"' => return,
+            _ => {}
+        }
+    }
+}
+
+/// Skip a character literal, but leave lifetimes and labels alone.
+fn skip_char(code: &str, index: usize, chars: &mut impl Iterator<Item = (usize, char)>) {
+    let rest = &code[index + 1..];
+    let mut rest_chars = rest.chars();
+
+    let length = match rest_chars.next() {
+        Some('\\') => match rest.get(2..).and_then(|r| r.find('\'')) {
+            Some(end) => end + 3,
+            None => return,
+        },
+        Some(c) if rest_chars.next() == Some('\'') => c.len_utf8() + 1,
+        _ => return,
+    };
+
+    let consumed = rest[..length].chars().count();
+    for _ in 0..consumed {
+        chars.next();
+    }
+}
+
+/// Checks the spec against the grammar of `std::fmt`:
+/// `[[fill]align][sign]['#']['0'][width]['.' precision][type]`.
+///
+/// Precision given as `*` is not supported, as it requires an extra argument.
+fn is_format_spec(spec: &str) -> bool {
+    if spec.is_empty() {
+        return false;
+    }
+
+    let mut rest = spec;
+
+    let mut chars = rest.chars();
+    let first = chars.next();
+    let second = chars.next();
+    if second.is_some_and(is_align) {
+        rest = &rest[first.map_or(0, char::len_utf8) + 1..];
+    } else if first.is_some_and(is_align) {
+        rest = &rest[1..];
+    }
+
+    rest = rest.strip_prefix(['+', '-']).unwrap_or(rest);
+    rest = rest.strip_prefix('#').unwrap_or(rest);
+    rest = rest.strip_prefix('0').unwrap_or(rest);
+    rest = strip_count(rest);
+
+    if let Some(precision) = rest.strip_prefix('.') {
+        let stripped = strip_count(precision);
+        if stripped.len() == precision.len() {
+            return false;
+        }
+        rest = stripped;
+    }
+
+    matches!(
+        rest,
+        "" | "?" | "x?" | "X?" | "x" | "X" | "o" | "b" | "e" | "E" | "p"
+    )
+}
+
+fn is_align(c: char) -> bool {
+    matches!(c, '<' | '^' | '>')
+}
+
+/// Strip a count, which is either an integer or an argument like `name$`.
+fn strip_count(input: &str) -> &str {
+    let digits = input.len() - input.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    if digits > 0 {
+        let rest = &input[digits..];
+        return rest.strip_prefix('$').unwrap_or(rest);
+    }
+
+    let identifier = input
+        .char_indices()
+        .take_while(|(i, c)| c.is_alphabetic() || *c == '_' || (*i > 0 && c.is_alphanumeric()))
+        .map(|(i, c)| i + c.len_utf8())
+        .last()
+        .unwrap_or(0);
+
+    match input[identifier..].strip_prefix('$') {
+        Some(rest) if identifier > 0 => rest,
+        _ => input,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::parser::Code;
+
+    use super::{is_format_spec, split_format_spec};
+
+    fn split(code: &str) -> (String, Option<String>) {
+        let (code, spec) = split_format_spec(Code::from(code));
+        (code.to_string(), spec)
+    }
+
+    #[test]
+    fn format_specs() {
+        for spec in [
+            ".2", ">8", "#x", "?", "#?", "08.3", "*^10", "+e", "width$", ".prec$", "<5.1$",
+        ] {
+            assert!(is_format_spec(spec), "Should be a format spec: {}", spec);
+        }
+
+        for spec in ["", "y", ".", ".*", "2x2", "name", "<<<"] {
+            assert!(
+                !is_format_spec(spec),
+                "Should not be a format spec: {}",
+                spec
+            );
+        }
+    }
+
+    #[test]
+    fn split_expression() {
+        assert_eq!(
+            split("self.price:.2"),
+            ("self.price".into(), Some(".2".into()))
+        );
+        assert_eq!(
+            split(" self.id : #x "),
+            (" self.id ".into(), Some("#x".into()))
+        );
+        assert_eq!(
+            split("std::f64::consts::PI:>8.3"),
+            ("std::f64::consts::PI".into(), Some(">8.3".into()))
+        );
+    }
+
+    #[test]
+    fn split_without_spec() {
+        assert_eq!(split("a::b"), ("a::b".into(), None));
+        assert_eq!(split("Point { x: 1 }"), ("Point { x: 1 }".into(), None));
+        assert_eq!(split("\"a:?\""), ("\"a:?\"".into(), None));
+        assert_eq!(split("':'"), ("':'".into(), None));
+        assert_eq!(split("'\\''"), ("'\\''".into(), None));
+        assert_eq!(split("'a: loop {}"), ("'a: loop {}".into(), None));
+    }
+}
@@ -1,13 +1,31 @@
 use proc_macro2::Span;
 
 use syn::{
-    ext::IdentExt, parse::ParseStream, spanned::Spanned, Attribute, Ident, ItemStruct, LitStr,
+    ext::IdentExt, parse::ParseStream, spanned::Spanned, Attribute, Ident, ItemStruct, LitBool,
+    LitStr, Path,
 };
 
+use crate::parser::ParseOptions;
+
 #[derive(Debug, Default)]
 pub(crate) struct TemplateOptions {
     pub(crate) content: Option<String>,
     pub(crate) path: Option<String>,
+    /// The string literal declaring the content or path, used to locate errors.
+    pub(crate) literal: Option<LitStr>,
+    pub(crate) trim_blocks: Option<bool>,
+    pub(crate) strip_html_comments: Option<bool>,
+    pub(crate) escape: Option<EscapeMode>,
+}
+
+/// The output language of a template, deciding how expressions are escaped.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum EscapeMode {
+    Html,
+    Xml,
+    None,
+    /// A path to a value implementing `zinal::Escaper`.
+    Custom(Path),
 }
 
 impl TemplateOptions {
@@ -33,6 +51,7 @@ impl TemplateOptions {
                 // Shorthand for template(content = "Foo")
                 let content = input.parse::<LitStr>().expect("Lookahead");
                 parsed.set_content(content.value(), content.span())?;
+                parsed.literal = Some(content);
             } else if lookahead.peek(Ident::peek_any) {
                 // template(x = "...")
                 // where x could be path or content
@@ -44,12 +63,48 @@ impl TemplateOptions {
                         input.parse::<Token![=]>()?;
                         let content = input.parse::<LitStr>()?;
                         parsed.set_content(content.value(), content.span())?;
+                        parsed.literal = Some(content);
                     }
 
                     "path" => {
                         input.parse::<Token![=]>()?;
                         let path = input.parse::<LitStr>()?;
                         parsed.set_path(path.value(), path.span())?;
+                        parsed.literal = Some(path);
+                    }
+
+                    "trim_blocks" => {
+                        input.parse::<Token![=]>()?;
+                        let trim_blocks = input.parse::<LitBool>()?;
+                        parsed.set_trim_blocks(trim_blocks.value, trim_blocks.span())?;
+                    }
+
+                    "strip_html_comments" => {
+                        input.parse::<Token![=]>()?;
+                        let strip = input.parse::<LitBool>()?;
+                        parsed.set_strip_html_comments(strip.value, strip.span())?;
+                    }
+
+                    "escape" => {
+                        input.parse::<Token![=]>()?;
+                        let span = input.span();
+                        let escape = if input.peek(LitStr) {
+                            let mode = input.parse::<LitStr>()?;
+                            match mode.value().as_str() {
+                                "html" => EscapeMode::Html,
+                                "xml" => EscapeMode::Xml,
+                                "none" => EscapeMode::None,
+                                other => {
+                                    return Err(syn::Error::new_spanned(
+                                        mode,
+                                        format!("Unknown escape mode `{}`, expected `html`, `xml`, `none` or the path to an escaper", other),
+                                    ))
+                                }
+                            }
+                        } else {
+                            EscapeMode::Custom(input.parse::<Path>()?)
+                        };
+                        parsed.set_escape(escape, span)?;
                     }
 
                     _ => {
@@ -85,6 +140,22 @@ impl TemplateOptions {
             self.set_path(path, attr.span())?;
         }
 
+        if parsed.literal.is_some() {
+            self.literal = parsed.literal;
+        }
+
+        if let Some(trim_blocks) = parsed.trim_blocks {
+            self.set_trim_blocks(trim_blocks, attr.span())?;
+        }
+
+        if let Some(strip) = parsed.strip_html_comments {
+            self.set_strip_html_comments(strip, attr.span())?;
+        }
+
+        if let Some(escape) = parsed.escape {
+            self.set_escape(escape, attr.span())?;
+        }
+
         Ok(())
     }
 
@@ -106,6 +177,67 @@ impl TemplateOptions {
         }
     }
 
+    pub(crate) fn set_trim_blocks(
+        &mut self,
+        trim_blocks: bool,
+        span: Span,
+    ) -> Result<(), syn::Error> {
+        if self.trim_blocks.is_none() {
+            self.trim_blocks.replace(trim_blocks);
+            Ok(())
+        } else {
+            Err(syn::Error::new(span, "Duplicate trim_blocks declaration"))
+        }
+    }
+
+    pub(crate) fn set_strip_html_comments(
+        &mut self,
+        strip_html_comments: bool,
+        span: Span,
+    ) -> Result<(), syn::Error> {
+        if self.strip_html_comments.is_none() {
+            self.strip_html_comments.replace(strip_html_comments);
+            Ok(())
+        } else {
+            Err(syn::Error::new(
+                span,
+                "Duplicate strip_html_comments declaration",
+            ))
+        }
+    }
+
+    pub(crate) fn set_escape(&mut self, escape: EscapeMode, span: Span) -> Result<(), syn::Error> {
+        if self.escape.is_none() {
+            self.escape.replace(escape);
+            Ok(())
+        } else {
+            Err(syn::Error::new(span, "Duplicate escape declaration"))
+        }
+    }
+
+    /// The declared escape mode, or else the mode matching the extension of
+    /// the template file.
+    pub(crate) fn escape_mode(&self) -> Option<EscapeMode> {
+        if self.escape.is_some() {
+            return self.escape.clone();
+        }
+
+        let extension = std::path::Path::new(self.path.as_ref()?).extension()?;
+        match extension.to_str()? {
+            "html" | "htm" => Some(EscapeMode::Html),
+            "xml" => Some(EscapeMode::Xml),
+            "txt" | "md" => Some(EscapeMode::None),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn parse_options(&self) -> ParseOptions {
+        ParseOptions {
+            trim_blocks: self.trim_blocks.unwrap_or(false),
+            strip_html_comments: self.strip_html_comments.unwrap_or(false),
+        }
+    }
+
     pub(crate) fn validate(&self) -> Result<(), syn::Error> {
         if self.content.is_none() && self.path.is_none() {
             return Err(syn::Error::new(
@@ -130,7 +262,7 @@ mod tests {
 
     use syn::{parse_quote, Attribute};
 
-    use super::TemplateOptions;
+    use super::{EscapeMode, TemplateOptions};
 
     #[test]
     fn parse_empty_options() {
@@ -187,4 +319,85 @@ mod tests {
             true
         }));
     }
+
+    #[test]
+    fn parse_trim_blocks() {
+        let attr: Attribute = parse_quote! {
+            #[template(content = "Test", trim_blocks = true)]
+        };
+
+        let result = attr.parse_args_with(TemplateOptions::parse_attr);
+
+        assert!(result.is_ok_and(|o| {
+            assert_eq!(o.trim_blocks, Some(true));
+            true
+        }));
+    }
+
+    #[test]
+    fn parse_strip_html_comments() {
+        let attr: Attribute = parse_quote! {
+            #[template(content = "Test", strip_html_comments = true)]
+        };
+
+        let result = attr.parse_args_with(TemplateOptions::parse_attr);
+
+        assert!(result.is_ok_and(|o| {
+            assert_eq!(o.strip_html_comments, Some(true));
+            true
+        }));
+    }
+
+    #[test]
+    fn parse_escape() {
+        let attr: Attribute = parse_quote! {
+            #[template(content = "Test", escape = "xml")]
+        };
+
+        let result = attr.parse_args_with(TemplateOptions::parse_attr);
+
+        assert!(result.is_ok_and(|o| {
+            assert_eq!(o.escape, Some(EscapeMode::Xml));
+            true
+        }));
+
+        let attr: Attribute = parse_quote! {
+            #[template(content = "Test", escape = latex::LatexEscaper)]
+        };
+
+        let result = attr.parse_args_with(TemplateOptions::parse_attr);
+
+        assert!(result.is_ok_and(|o| {
+            assert_eq!(
+                o.escape,
+                Some(EscapeMode::Custom(parse_quote!(latex::LatexEscaper)))
+            );
+            true
+        }));
+
+        let attr: Attribute = parse_quote! {
+            #[template(content = "Test", escape = "json")]
+        };
+
+        assert!(attr.parse_args_with(TemplateOptions::parse_attr).is_err());
+    }
+
+    #[test]
+    fn escape_mode_from_extension() {
+        let options = |path: &str| TemplateOptions {
+            path: Some(path.to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(options("mail.txt").escape_mode(), Some(EscapeMode::None));
+        assert_eq!(options("feed.xml").escape_mode(), Some(EscapeMode::Xml));
+        assert_eq!(options("page.html").escape_mode(), Some(EscapeMode::Html));
+        assert_eq!(options("page.tpl").escape_mode(), None);
+
+        let declared = TemplateOptions {
+            escape: Some(EscapeMode::Html),
+            ..options("mail.txt")
+        };
+        assert_eq!(declared.escape_mode(), Some(EscapeMode::Html));
+    }
 }
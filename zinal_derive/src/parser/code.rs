@@ -0,0 +1,161 @@
+use std::{borrow::Cow, ops::Deref, rc::Rc};
+
+use proc_macro2::{Group, Span, TokenStream, TokenTree};
+use syn::parse::Parse;
+
+use super::Origin;
+
+/// Rust code embedded in a template, together with the location it was
+/// declared at.
+#[derive(Debug, Clone)]
+pub struct Code<'src> {
+    text: Cow<'src, str>,
+    location: Option<CodeLocation<'src>>,
+}
+
+#[derive(Clone)]
+struct CodeLocation<'src> {
+    source: &'src str,
+    offset: usize,
+    origin: Rc<Origin>,
+}
+
+impl<'src> Code<'src> {
+    pub fn new(text: Cow<'src, str>, source: &'src str, offset: usize, origin: Rc<Origin>) -> Self {
+        Self {
+            text,
+            location: Some(CodeLocation {
+                source,
+                offset,
+                origin,
+            }),
+        }
+    }
+
+    /// Change the text of the code, keeping its location.
+    pub fn map(self, f: impl FnOnce(Cow<'src, str>) -> Cow<'src, str>) -> Self {
+        Self {
+            text: f(self.text),
+            location: self.location,
+        }
+    }
+
+    /// Parse the code. The resulting tokens are spanned to the location of the
+    /// code in the template, so that errors found by the compiler point there.
+    pub fn parse<T: Parse>(&self) -> Result<T, syn::Error> {
+        let tokens =
+            syn::parse_str::<TokenStream>(&self.text).map_err(|e| self.error(&e.to_string()))?;
+        let tokens = respan(tokens, self.span());
+
+        syn::parse2(tokens).map_err(|e| self.error(&e.to_string()))
+    }
+
+    /// The template file, line and column where the code starts, if known.
+    pub fn location(&self) -> Option<(Option<&str>, usize, usize)> {
+        let l = self.location.as_ref()?;
+        let (line, column) = l.origin.line_column(l.source, l.offset);
+
+        Some((l.origin.name(), line, column))
+    }
+
+    /// The most precise span available for the code.
+    pub fn span(&self) -> Span {
+        match &self.location {
+            Some(l) => l.origin.span_at(l.source, l.offset, self.text.len()),
+            None => Span::call_site(),
+        }
+    }
+
+    /// Create an error located where the code starts.
+    pub fn error(&self, message: &str) -> syn::Error {
+        match &self.location {
+            Some(l) => l.origin.error(l.source, l.offset, message),
+            None => syn::Error::new(Span::call_site(), message),
+        }
+    }
+}
+
+impl<'src> From<&'src str> for Code<'src> {
+    fn from(value: &'src str) -> Self {
+        Self {
+            text: Cow::Borrowed(value),
+            location: None,
+        }
+    }
+}
+
+impl Deref for Code<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.text
+    }
+}
+
+impl AsRef<str> for Code<'_> {
+    fn as_ref(&self) -> &str {
+        &self.text
+    }
+}
+
+/// Code is compared by its text only, as the location is only used for errors.
+impl PartialEq for Code<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.text == other.text
+    }
+}
+
+impl Eq for Code<'_> {}
+
+impl std::fmt::Debug for CodeLocation<'_> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CodeLocation")
+            .field("offset", &self.offset)
+            .finish_non_exhaustive()
+    }
+}
+
+fn respan(tokens: TokenStream, span: Span) -> TokenStream {
+    tokens
+        .into_iter()
+        .map(|mut token| {
+            if let TokenTree::Group(group) = &token {
+                let mut respanned = Group::new(group.delimiter(), respan(group.stream(), span));
+                respanned.set_span(span);
+                token = TokenTree::Group(respanned);
+            } else {
+                token.set_span(span);
+            }
+            token
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, rc::Rc};
+
+    use proc_macro2::Span;
+
+    use crate::parser::Origin;
+
+    use super::Code;
+
+    #[test]
+    fn syntax_error_location() {
+        let origin = Rc::new(Origin::file(
+            String::from("templates/test.html"),
+            Span::call_site(),
+        ));
+        let source = "<p>\n{{ self.name + }}</p>";
+        let code = Code::new(Cow::from("self.name +"), source, 7, origin);
+
+        let error = code.parse::<syn::Expr>().expect_err("Should not parse");
+
+        assert!(
+            error.to_string().contains("--> templates/test.html:2:4\n"),
+            "Unexpected error: {}",
+            error
+        );
+    }
+}
@@ -30,7 +30,7 @@ pub fn select3<'src>(
         return Ok(Some(result));
     }
 
-    return select2(input, (p2, p3));
+    select2(input, (p2, p3))
 }
 
 pub fn select4<'src>(
@@ -47,7 +47,7 @@ pub fn select4<'src>(
         return Ok(Some(result));
     }
 
-    return select3(input, (p2, p3, p4));
+    select3(input, (p2, p3, p4))
 }
 
 pub fn select5<'src>(
@@ -65,7 +65,7 @@ pub fn select5<'src>(
         return Ok(Some(result));
     }
 
-    return select4(input, (p2, p3, p4, p5));
+    select4(input, (p2, p3, p4, p5))
 }
 
 pub fn select6<'src>(
@@ -84,5 +84,5 @@ pub fn select6<'src>(
         return Ok(Some(result));
     }
 
-    return select5(input, (p2, p3, p4, p5, p6));
+    select5(input, (p2, p3, p4, p5, p6))
 }
@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use unicode_xid::UnicodeXID;
 
 use crate::parser::input::{Input, Offset};
@@ -58,6 +60,128 @@ pub fn parse_rust_typename<'src>(input: &mut Input<'src>) -> Option<Offset<'src>
     Some(input.combine(&parts))
 }
 
+/// Parse rust code up to and including the given terminator.
+///
+/// The terminator is not recognized within string and character literals or
+/// comments, and if `balanced` is set, also not within brackets. A terminator
+/// prefixed with `%` is always replaced by the plain terminator, so it can
+/// be used in places the scanner does not understand.
+///
+/// Returns `None` without consuming anything if the terminator is missing.
+pub fn parse_rust_code<'src>(
+    input: &mut Input<'src>,
+    terminator: &str,
+    balanced: bool,
+) -> Option<Cow<'src, str>> {
+    enum State {
+        Code,
+        Str,
+        RawStr(usize),
+        Char,
+        Comment,
+    }
+
+    let code = input.remainder();
+    let escape = format!("%{}", terminator);
+
+    let mut content = Cow::Borrowed("");
+    let mut state = State::Code;
+    let mut depth = 0usize;
+    let mut copied = 0;
+    let mut index = 0;
+
+    while index < code.len() {
+        let rest = &code[index..];
+
+        if rest.starts_with(&escape) {
+            append(&mut content, &code[copied..index]);
+            content.to_mut().push_str(terminator);
+
+            index += escape.len();
+            copied = index;
+            continue;
+        }
+
+        let c = rest.chars().next().expect("Should not be empty");
+        let mut len = c.len_utf8();
+
+        match state {
+            State::Code => {
+                if (depth == 0 || !balanced) && rest.starts_with(terminator) {
+                    append(&mut content, &code[copied..index]);
+                    input.consume_lit(&code[..index + terminator.len()]);
+
+                    return Some(content);
+                }
+
+                match c {
+                    '"' => state = State::Str,
+                    'r' => {
+                        let hashes = rest[1..].len() - rest[1..].trim_start_matches('#').len();
+                        if rest[1 + hashes..].starts_with('"') {
+                            state = State::RawStr(hashes);
+                            len += hashes + 1;
+                        }
+                    }
+                    '\'' => {
+                        // Either a char literal or a lifetime
+                        let mut chars = rest[1..].chars();
+                        match (chars.next(), chars.next()) {
+                            (Some('\\'), _) => state = State::Char,
+                            (Some(c), Some('\'')) => len += c.len_utf8() + 1,
+                            _ => {}
+                        }
+                    }
+                    '/' if rest.starts_with("/*") => {
+                        state = State::Comment;
+                        len = 2;
+                    }
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+            }
+            State::Str | State::Char => {
+                let closing = if matches!(state, State::Str) {
+                    '"'
+                } else {
+                    '\''
+                };
+
+                if c == '\\' {
+                    len += rest[1..].chars().next().map_or(0, char::len_utf8);
+                } else if c == closing {
+                    state = State::Code;
+                }
+            }
+            State::RawStr(hashes) => {
+                if c == '"' && rest[1..].starts_with(&"#".repeat(hashes)) {
+                    state = State::Code;
+                    len += hashes;
+                }
+            }
+            State::Comment => {
+                if rest.starts_with("*/") {
+                    state = State::Code;
+                    len = 2;
+                }
+            }
+        }
+
+        index += len;
+    }
+
+    return None;
+
+    fn append<'src>(content: &mut Cow<'src, str>, part: &'src str) {
+        if content.is_empty() {
+            *content = Cow::Borrowed(part);
+        } else {
+            content.to_mut().push_str(part);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::parser::{
@@ -65,7 +189,43 @@ mod tests {
         input::{Input, Offset},
     };
 
-    use super::parse_rust_identifier;
+    use super::{parse_rust_code, parse_rust_identifier};
+
+    #[test]
+    fn rust_code_valid() {
+        let valid_code = [
+            (r#"format!("{}}}", x) }}"#, r#"format!("{}}}", x) "#),
+            (r#"'}' }}"#, r#"'}' "#),
+            (r#"'\'' }}"#, r#"'\'' "#),
+            (r##"r#"}}"# }}"##, r##"r#"}}"# "##),
+            ("|x: &'a str| Foo { x } }}", "|x: &'a str| Foo { x } "),
+            ("x /* }} */ }}", "x /* }} */ "),
+            ("a %}} b }} c", "a }} b "),
+        ];
+
+        for (code, expected) in valid_code {
+            let mut input = Input::new(code);
+
+            let result = parse_rust_code(&mut input, "}}", true);
+
+            assert_eq!(result.as_deref(), Some(expected), "Parsing {}", code);
+        }
+    }
+
+    #[test]
+    fn rust_code_unterminated() {
+        let invalid_code = ["x", "\"}}\"", "Foo { x }}"];
+
+        for code in invalid_code {
+            let mut input = Input::new(code);
+
+            let position_before = input.position();
+            let result = parse_rust_code(&mut input, "}}", true);
+
+            assert_eq!(None, result);
+            assert_eq!(position_before, input.position());
+        }
+    }
 
     #[test]
     fn rust_identifier_valid() {
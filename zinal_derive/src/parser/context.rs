@@ -0,0 +1,643 @@
+use super::{EscapeContext, Item, Keyword};
+
+/// Determine the HTML context of every expression from the literal content
+/// preceding it, so it can be escaped accordingly.
+///
+/// The template is followed in document order. Branches of `if` and `match`
+/// statements each start with the context before the statement.
+pub fn assign_contexts(items: &mut [Item<'_>]) {
+    let mut state = State::Text;
+    assign(items, &mut state);
+}
+
+fn assign(items: &mut [Item<'_>], state: &mut State) {
+    let mut branch_start = None;
+
+    for item in items {
+        match item {
+            Item::Literal(text) => state.feed(text),
+
+            Item::Expression(code, context) => {
+                if code.as_ref() != "@children" {
+                    *context = state.context();
+                    state.advance();
+                }
+            }
+
+            Item::KeywordStatement { keyword, body, .. } => match keyword {
+                Keyword::If | Keyword::Match => {
+                    branch_start = Some(state.clone());
+                    assign(body, state);
+                }
+                Keyword::Else | Keyword::ElseIf => {
+                    if let Some(start) = &branch_start {
+                        *state = start.clone();
+                    }
+                    assign(body, state);
+                }
+                Keyword::Case => {
+                    let start = state.clone();
+                    assign(body, state);
+                    *state = start;
+                }
+                _ => assign(body, state),
+            },
+
+            Item::Block { body, .. } => assign(body, state),
+
+            // Children are rendered wherever the child template places them
+            Item::ChildTemplate {
+                children, slots, ..
+            } => {
+                assign(children, &mut State::Text);
+                for slot in slots {
+                    assign(&mut slot.children, &mut State::Text);
+                }
+            }
+
+            Item::UnescapedExpression(_)
+            | Item::PlainStatement(_)
+            | Item::Slot { .. }
+            | Item::Extends(_) => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum State {
+    Text,
+    Comment,
+    Tag { name: String, position: TagPosition },
+    Script(Script),
+    Style,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TagPosition {
+    Name,
+    BeforeAttribute,
+    AttributeName(String),
+    AfterAttributeName(String),
+    BeforeValue(String),
+    Value {
+        name: String,
+        quote: Option<char>,
+        start: bool,
+        /// The script of event handler attributes
+        script: Option<Script>,
+    },
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Script {
+    quote: Option<char>,
+    escaped: bool,
+    comment: Option<Comment>,
+    regex: Option<Regex>,
+    /// Whether the preceding token is a value, so a `/` is a division
+    /// instead of the start of a regular expression literal.
+    after_value: bool,
+    /// The last identifier or keyword, and whether it is still being read.
+    word: String,
+    in_word: bool,
+    /// Whether each open parenthesis follows a control keyword like `if`,
+    /// after which a `/` starts a regular expression.
+    parens: Vec<bool>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Regex {
+    class: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Comment {
+    Line,
+    Block,
+}
+
+impl State {
+    fn context(&self) -> EscapeContext {
+        match self {
+            State::Text | State::Comment => EscapeContext::Html,
+            State::Script(script) if script.quote.is_some() => EscapeContext::ScriptString,
+            State::Script(_) => EscapeContext::Script,
+            State::Style => EscapeContext::Style,
+            State::Tag { position, .. } => match position {
+                TagPosition::BeforeValue(name) if is_event_handler(name) => {
+                    EscapeContext::EventHandler
+                }
+                TagPosition::BeforeValue(name) if is_style_attribute(name) => {
+                    EscapeContext::StyleAttribute
+                }
+                TagPosition::BeforeValue(name) if is_url_attribute(name) => EscapeContext::Url,
+                TagPosition::BeforeValue(_) => EscapeContext::Attribute,
+                TagPosition::Value {
+                    script: Some(script),
+                    ..
+                } => match script.quote {
+                    Some(_) => EscapeContext::EventHandlerString,
+                    None => EscapeContext::EventHandler,
+                },
+                TagPosition::Value { name, .. } if is_style_attribute(name) => {
+                    EscapeContext::StyleAttribute
+                }
+                TagPosition::Value { name, start, .. } if is_url_attribute(name) => match start {
+                    true => EscapeContext::Url,
+                    false => EscapeContext::UrlPart,
+                },
+                TagPosition::Value { quote: None, .. } => EscapeContext::Attribute,
+                _ => EscapeContext::Html,
+            },
+        }
+    }
+
+    /// Update the state after an expression was rendered.
+    fn advance(&mut self) {
+        if let State::Script(script) = self {
+            script.after_value = true;
+        }
+
+        if let State::Tag { position, .. } = self {
+            match position {
+                TagPosition::BeforeValue(name) => {
+                    *position = TagPosition::value(std::mem::take(name), None, false);
+                }
+                TagPosition::Value { start, script, .. } => {
+                    *start = false;
+                    if let Some(script) = script {
+                        script.after_value = true;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn feed(&mut self, text: &str) {
+        let mut rest = text;
+
+        while let Some(c) = rest.chars().next() {
+            let length = self.step(c, rest);
+            rest = &rest[length..];
+        }
+    }
+
+    /// Process the next character, returning the number of bytes consumed.
+    fn step(&mut self, c: char, rest: &str) -> usize {
+        let length = c.len_utf8();
+
+        match self {
+            State::Text => {
+                if rest.starts_with("<!--") {
+                    *self = State::Comment;
+                    return 4;
+                }
+                if let Some(tag) = rest.strip_prefix('<') {
+                    let (name, tag) = match tag.strip_prefix('/') {
+                        Some(tag) => (String::from("/"), tag),
+                        None => (String::new(), tag),
+                    };
+                    if tag.starts_with(|c: char| c.is_ascii_alphabetic()) {
+                        *self = State::Tag {
+                            name,
+                            position: TagPosition::Name,
+                        };
+                        return rest.len() - tag.len();
+                    }
+                }
+            }
+
+            State::Comment => {
+                if rest.starts_with("-->") {
+                    *self = State::Text;
+                    return 3;
+                }
+            }
+
+            State::Script(script) => {
+                if starts_with_ignore_case(rest, "</script") {
+                    *self = State::Tag {
+                        name: String::from("/"),
+                        position: TagPosition::Name,
+                    };
+                    return 2;
+                }
+                script.step(c, rest);
+            }
+
+            State::Style => {
+                if starts_with_ignore_case(rest, "</style") {
+                    *self = State::Tag {
+                        name: String::from("/"),
+                        position: TagPosition::Name,
+                    };
+                    return 2;
+                }
+            }
+
+            State::Tag { name, position } => {
+                if c == '>' && !matches!(position, TagPosition::Value { quote: Some(_), .. }) {
+                    *self = match name.to_ascii_lowercase().as_str() {
+                        "script" => State::Script(Script::default()),
+                        "style" => State::Style,
+                        _ => State::Text,
+                    };
+                    return length;
+                }
+
+                match position {
+                    TagPosition::Name => {
+                        if c.is_whitespace() || c == '/' {
+                            *position = TagPosition::BeforeAttribute;
+                        } else {
+                            name.push(c);
+                        }
+                    }
+                    TagPosition::BeforeAttribute => {
+                        if !c.is_whitespace() && c != '/' {
+                            *position = TagPosition::AttributeName(c.to_string());
+                        }
+                    }
+                    TagPosition::AttributeName(attribute) => {
+                        if c == '=' {
+                            *position = TagPosition::BeforeValue(std::mem::take(attribute));
+                        } else if c.is_whitespace() {
+                            *position = TagPosition::AfterAttributeName(std::mem::take(attribute));
+                        } else if c == '/' {
+                            *position = TagPosition::BeforeAttribute;
+                        } else {
+                            attribute.push(c);
+                        }
+                    }
+                    TagPosition::AfterAttributeName(attribute) => {
+                        if c == '=' {
+                            *position = TagPosition::BeforeValue(std::mem::take(attribute));
+                        } else if !c.is_whitespace() {
+                            *position = TagPosition::AttributeName(c.to_string());
+                        }
+                    }
+                    TagPosition::BeforeValue(attribute) => {
+                        if c == '"' || c == '\'' {
+                            *position =
+                                TagPosition::value(std::mem::take(attribute), Some(c), true);
+                        } else if !c.is_whitespace() {
+                            let mut value =
+                                TagPosition::value(std::mem::take(attribute), None, false);
+                            if let TagPosition::Value {
+                                script: Some(script),
+                                ..
+                            } = &mut value
+                            {
+                                script.step(c, rest);
+                            }
+                            *position = value;
+                        }
+                    }
+                    TagPosition::Value {
+                        quote,
+                        start,
+                        script,
+                        ..
+                    } => {
+                        if Some(c) == *quote || (quote.is_none() && c.is_whitespace()) {
+                            *position = TagPosition::BeforeAttribute;
+                        } else {
+                            *start = false;
+                            if let Some(script) = script {
+                                script.step(c, rest);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        length
+    }
+}
+
+impl TagPosition {
+    fn value(name: String, quote: Option<char>, start: bool) -> Self {
+        let script = is_event_handler(&name).then(Script::default);
+
+        TagPosition::Value {
+            name,
+            quote,
+            start,
+            script,
+        }
+    }
+}
+
+impl Script {
+    fn step(&mut self, c: char, rest: &str) {
+        if let Some(regex) = &mut self.regex {
+            if self.escaped {
+                self.escaped = false;
+            } else if c == '\\' {
+                self.escaped = true;
+            } else if c == '[' {
+                regex.class = true;
+            } else if c == ']' {
+                regex.class = false;
+            } else if c == '/' && !regex.class {
+                self.regex = None;
+                self.after_value = true;
+            }
+            return;
+        }
+
+        match (self.comment, self.quote) {
+            (Some(Comment::Line), _) => {
+                if c == '\n' {
+                    self.comment = None;
+                }
+            }
+            (Some(Comment::Block), _) => {
+                if rest.starts_with("*/") {
+                    self.comment = None;
+                }
+            }
+            (None, Some(quote)) => {
+                if self.escaped {
+                    self.escaped = false;
+                } else if c == '\\' {
+                    self.escaped = true;
+                } else if c == quote {
+                    self.quote = None;
+                    self.after_value = true;
+                }
+            }
+            (None, None) => {
+                if rest.starts_with("//") {
+                    self.comment = Some(Comment::Line);
+                    return;
+                }
+                if rest.starts_with("/*") {
+                    self.comment = Some(Comment::Block);
+                    return;
+                }
+
+                if c.is_alphanumeric() || c == '_' || c == '$' {
+                    if !self.in_word {
+                        self.word.clear();
+                        self.in_word = true;
+                    }
+                    self.word.push(c);
+                    self.after_value = !is_regex_keyword(&self.word);
+                    return;
+                }
+
+                self.in_word = false;
+                if c.is_whitespace() {
+                    return;
+                }
+
+                let word = std::mem::take(&mut self.word);
+                match c {
+                    '"' | '\'' | '`' => self.quote = Some(c),
+                    '/' if self.after_value => self.after_value = false,
+                    '/' => self.regex = Some(Regex::default()),
+                    '(' => {
+                        let control = matches!(word.as_str(), "if" | "while" | "for" | "with");
+                        self.parens.push(control);
+                        self.after_value = false;
+                    }
+                    ')' => self.after_value = !self.parens.pop().unwrap_or(false),
+                    ']' => self.after_value = true,
+                    _ => self.after_value = false,
+                }
+            }
+        }
+    }
+}
+
+/// Keywords after which a `/` starts a regular expression literal.
+fn is_regex_keyword(word: &str) -> bool {
+    matches!(
+        word,
+        "return"
+            | "typeof"
+            | "instanceof"
+            | "in"
+            | "of"
+            | "new"
+            | "delete"
+            | "void"
+            | "throw"
+            | "case"
+            | "do"
+            | "else"
+            | "yield"
+            | "await"
+    )
+}
+
+fn is_event_handler(name: &str) -> bool {
+    name.len() > 2 && name.get(..2).is_some_and(|p| p.eq_ignore_ascii_case("on"))
+}
+
+fn is_style_attribute(name: &str) -> bool {
+    name.eq_ignore_ascii_case("style")
+}
+
+fn is_url_attribute(name: &str) -> bool {
+    matches!(
+        name.to_ascii_lowercase().as_str(),
+        "href"
+            | "src"
+            | "action"
+            | "formaction"
+            | "cite"
+            | "poster"
+            | "background"
+            | "longdesc"
+            | "usemap"
+            | "codebase"
+            | "data"
+            | "manifest"
+            | "icon"
+            | "xlink:href"
+    )
+}
+
+fn starts_with_ignore_case(text: &str, prefix: &str) -> bool {
+    text.get(..prefix.len())
+        .is_some_and(|start| start.eq_ignore_ascii_case(prefix))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::parser::{Code, EscapeContext, Item, Keyword};
+
+    use super::assign_contexts;
+
+    fn contexts(template: &[&str]) -> Vec<EscapeContext> {
+        let mut items = template
+            .iter()
+            .enumerate()
+            .map(|(index, part)| match index % 2 {
+                0 => Item::Literal(Cow::Borrowed(*part)),
+                _ => Item::Expression(Code::from(*part), EscapeContext::Html),
+            })
+            .collect::<Vec<_>>();
+
+        assign_contexts(&mut items);
+
+        items
+            .into_iter()
+            .filter_map(|item| match item {
+                Item::Expression(_, context) => Some(context),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn text_and_attributes() {
+        assert_eq!(
+            contexts(&[
+                "<p class=\"a ",
+                "x",
+                "\" title=",
+                "x",
+                "><!-- <script> -->",
+                "x",
+                "</p>"
+            ]),
+            vec![
+                EscapeContext::Html,
+                EscapeContext::Attribute,
+                EscapeContext::Html
+            ]
+        );
+    }
+
+    #[test]
+    fn url_attributes() {
+        assert_eq!(
+            contexts(&[
+                "<a href=\"",
+                "x",
+                "/path?q=",
+                "x",
+                "\"><img SRC=",
+                "x",
+                "></a>"
+            ]),
+            vec![
+                EscapeContext::Url,
+                EscapeContext::UrlPart,
+                EscapeContext::Url
+            ]
+        );
+    }
+
+    #[test]
+    fn script_and_style() {
+        assert_eq!(
+            contexts(&[
+                "<script type=\"module\">let a = ",
+                "x",
+                "; let b = 'it\\'s ",
+                "x",
+                "'; // don't\n</script><style>p { color: ",
+                "x",
+                " }</STYLE>",
+                "x",
+            ]),
+            vec![
+                EscapeContext::Script,
+                EscapeContext::ScriptString,
+                EscapeContext::Style,
+                EscapeContext::Html
+            ]
+        );
+    }
+
+    #[test]
+    fn event_handler_and_style_attributes() {
+        assert_eq!(
+            contexts(&[
+                "<a onclick=\"go('",
+                "x",
+                "', ",
+                "x",
+                ")\" ONMOUSEOVER=",
+                "x",
+                " style=\"color: ",
+                "x",
+                "\" style=",
+                "x",
+                ">"
+            ]),
+            vec![
+                EscapeContext::EventHandlerString,
+                EscapeContext::EventHandler,
+                EscapeContext::EventHandler,
+                EscapeContext::StyleAttribute,
+                EscapeContext::StyleAttribute
+            ]
+        );
+    }
+
+    #[test]
+    fn regex_literals() {
+        assert_eq!(
+            contexts(&[
+                "<script>let r = /\"[/]/g; let s = ",
+                "x",
+                "; if (a) /'/.test(b); let d = (a) / 2 + '",
+                "x",
+                "'; return /`/, ",
+                "x",
+                " / 2 / ",
+                "x",
+                "</script>"
+            ]),
+            vec![
+                EscapeContext::Script,
+                EscapeContext::ScriptString,
+                EscapeContext::Script,
+                EscapeContext::Script
+            ]
+        );
+    }
+
+    #[test]
+    fn branches() {
+        let mut items = vec![
+            Item::Literal(Cow::Borrowed("<a href=")),
+            Item::KeywordStatement {
+                keyword: Keyword::If,
+                label: None,
+                statement: Some(Code::from("a")),
+                body: vec![
+                    Item::Literal(Cow::Borrowed("\"")),
+                    Item::Expression(Code::from("x"), EscapeContext::Html),
+                    Item::Literal(Cow::Borrowed("\"")),
+                ],
+            },
+            Item::KeywordStatement {
+                keyword: Keyword::Else,
+                label: None,
+                statement: None,
+                body: vec![Item::Expression(Code::from("y"), EscapeContext::Html)],
+            },
+        ];
+
+        assign_contexts(&mut items);
+
+        assert!(matches!(
+            &items[1],
+            Item::KeywordStatement { body, .. } if body[1] == Item::Expression(Code::from("x"), EscapeContext::Url)
+        ));
+        assert!(matches!(
+            &items[2],
+            Item::KeywordStatement { body, .. } if body[0] == Item::Expression(Code::from("y"), EscapeContext::Url)
+        ));
+    }
+}
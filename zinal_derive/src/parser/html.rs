@@ -1,11 +1,9 @@
 use std::borrow::Cow;
 
-use proc_macro2::Span;
-
 use crate::parser::{
-    common::{parse_rust_identifier, select2},
-    input::Offset,
-    Keyword, TemplateArgument, TemplateArgumentValue,
+    common::{parse_rust_code, parse_rust_identifier, select2, select5},
+    input::{Offset, Position, Trim},
+    Code, EscapeContext, Keyword, TemplateArgument, TemplateArgumentValue, TemplateSlot,
 };
 
 use super::{
@@ -19,9 +17,34 @@ pub struct HtmlParser;
 impl HtmlParser {
     pub fn parse<'src>(&mut self, mut input: Input<'src>) -> Result<Vec<Item<'src>>, syn::Error> {
         let mut items = Vec::new();
+        let mut extends = false;
+        let mut content = None;
 
         while !input.is_at_end() {
-            items.push(parse_template_item(&mut input)?.expect("Should never be None"));
+            let position = input.position();
+            let item = parse_template_item(&mut input)?.expect("Should never be None");
+
+            match &item {
+                Item::Extends(_) if extends => {
+                    return Err(
+                        input.error_at(position, "A template can only extend one base template")
+                    );
+                }
+                Item::Extends(_) => extends = true,
+                Item::Block { .. } => {}
+                Item::Literal(text) if text.trim().is_empty() => {}
+                _ => {
+                    content.get_or_insert(position);
+                }
+            }
+
+            items.push(item);
+        }
+
+        if let (true, Some(position)) = (extends, content) {
+            input.reset_to(position);
+            input.consume_while(char::is_whitespace);
+            return Err(input.error("Templates extending a base template must only contain blocks"));
         }
 
         Ok(items)
@@ -34,8 +57,8 @@ fn parse_template_item<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
         (
             parse_escape,
             parse_expression,
-            parse_statement,
             parse_comment,
+            parse_statement,
             parse_child_template,
             parse_literal,
         ),
@@ -55,86 +78,191 @@ fn parse_escape<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
 }
 
 fn parse_expression<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+    let start = input.position();
+
     if input.consume_lit("{{").is_none() {
         return Ok(None);
     }
 
-    let mut content = Cow::<'src, str>::Borrowed("");
+    let unescaped = has_unescaped_marker(input.remainder());
+    if unescaped {
+        input.consume_lit("!");
+    } else if has_expression_trim_marker(input.remainder()) {
+        input.consume_lit("-");
+    }
 
-    loop {
-        let part = input.consume_until_any("}%");
+    if !unescaped {
+        if let Some(slot) = parse_slot_reference(input) {
+            return Ok(Some(slot));
+        }
+    }
 
-        append(&mut content, part.into_str());
+    input.consume_while(char::is_whitespace);
 
-        if input.consume_lit("%}}").is_some() {
-            append(&mut content, "}}");
-        } else if input.consume_lit("}}").is_some() {
-            break;
-        } else if input.consume_lit("}").is_some() {
-            append(&mut content, "}");
-        } else if input.consume_lit("%").is_some() {
-            append(&mut content, "%");
-        } else {
-            return Err(syn::Error::new(
-                Span::call_site(),
-                "Unterminated expression",
-            ));
+    let position = input.position();
+
+    let Some(content) = parse_rust_code(input, "}}", true) else {
+        return Err(input.error_at(start, "Unterminated expression"));
+    };
+
+    let mut content = trim(content);
+
+    if let Some(stripped) = content.strip_suffix('-') {
+        if stripped.ends_with(char::is_whitespace) {
+            content = trim(slice(content, |c| &c[..c.len() - 1]));
+            input.trim_here(Trim::Whitespace);
         }
     }
 
-    Ok(Some(Item::Expression(trim(content))))
+    let code = input.code(content, position);
+
+    if unescaped {
+        Ok(Some(Item::UnescapedExpression(code)))
+    } else {
+        Ok(Some(Item::Expression(code, EscapeContext::default())))
+    }
+}
+
+fn parse_slot_reference<'src>(input: &mut Input<'src>) -> Option<Item<'src>> {
+    let position = input.position();
+
+    input.consume_while(char::is_whitespace);
+
+    if input.consume_lit("@slot").is_none() {
+        input.reset_to(position);
+        return None;
+    }
+
+    let optional = input.consume_lit("?").is_some();
+
+    if input.consume_while(char::is_whitespace).is_empty() {
+        input.reset_to(position);
+        return None;
+    }
+
+    let Some(name) = parse_rust_identifier(input).map(Offset::into_cow) else {
+        input.reset_to(position);
+        return None;
+    };
+
+    input.consume_while(char::is_whitespace);
+
+    let trim_after = input.consume_lit("-").is_some();
+
+    if input.consume_lit("}}").is_none() {
+        input.reset_to(position);
+        return None;
+    }
+
+    if trim_after {
+        input.trim_here(Trim::Whitespace);
+    }
+
+    Some(Item::Slot { name, optional })
 }
 
 fn parse_statement<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
-    return select2(input, (parse_keyword_statement, parse_plain_statement));
+    return select5(
+        input,
+        (
+            parse_raw_directive,
+            parse_extends_directive,
+            parse_block_directive,
+            parse_keyword_statement,
+            parse_plain_statement,
+        ),
+    );
 
     fn parse_keyword_statement<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
         let position = input.position();
 
-        if input.consume_lit("<#").is_none() {
+        if !parse_statement_start(input) {
             return Ok(None);
         }
 
         input.consume_while(char::is_whitespace);
 
+        let label = parse_label(input);
+
         let Some(keyword) = parse_keyword(input) else {
             input.reset_to(position);
             return Ok(None);
         };
 
+        if label.is_some() && !keyword.is_loop() {
+            return Err(input.error_at(position, "Only loops can be labeled"));
+        }
+
         let whitespace = input.consume_while(char::is_whitespace);
 
-        let statement = if input
-            .consume_lit(">")
-            .or_else(|| input.consume_lit("#>"))
-            .is_some()
-        {
+        let statement = if parse_statement_end(input) {
             // Shorthand form
             None
         } else {
             // Long form
 
-            if whitespace.len() < 1 {
+            if whitespace.is_empty() {
                 // In long form at least one whitespace is needed to separate
                 // the keyword from the statement content
                 input.reset_to(position);
                 return Ok(None);
             }
-            Some(parse_statement_content(input)?)
+            Some(parse_statement_content(input, position)?)
         };
 
-        let body = if keyword.has_body() {
-            parse_body(input)?
-        } else {
-            Vec::new()
+        let statement = match (keyword, statement) {
+            (Keyword::Match, None) => {
+                return Err(input.error_at(position, "Missing expression in match statement"));
+            }
+            (Keyword::Case, None) => {
+                return Err(input.error_at(position, "Missing pattern in case statement"));
+            }
+            (Keyword::Let, None) => {
+                return Err(input.error_at(position, "Missing pattern in let statement"));
+            }
+            (Keyword::Case, statement) => statement.map(trim_case_arrow),
+            (_, statement) => statement,
+        };
+
+        let body = match keyword {
+            Keyword::Match => parse_match_arms(input)?,
+            // The end of the last case also ends the match statement
+            Keyword::Case => parse_case_body(input)?,
+            _ if keyword.has_body() => parse_body(input)?,
+            _ => Vec::new(),
         };
 
         return Ok(Some(Item::KeywordStatement {
             keyword,
+            label,
             statement,
             body,
         }));
 
+        fn parse_label<'src>(input: &mut Input<'src>) -> Option<Cow<'src, str>> {
+            let position = input.position();
+
+            let tick = input.consume_lit("'")?;
+
+            let Some(ident) = parse_rust_identifier(input) else {
+                input.reset_to(position);
+                return None;
+            };
+
+            let label = input.combine(&[tick, ident]);
+
+            input.consume_while(char::is_whitespace);
+
+            if input.consume_lit(":").is_none() {
+                input.reset_to(position);
+                return None;
+            }
+
+            input.consume_while(char::is_whitespace);
+
+            Some(label.into_cow())
+        }
+
         fn parse_keyword(input: &mut Input<'_>) -> Option<Keyword> {
             if input.consume_lit("if").is_some() {
                 return Some(Keyword::If);
@@ -180,88 +308,360 @@ fn parse_statement<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
                 return Some(Keyword::Let);
             }
 
+            if input.consume_lit("match").is_some() {
+                return Some(Keyword::Match);
+            }
+
+            if input.consume_lit("case").is_some() {
+                return Some(Keyword::Case);
+            }
+
             None
         }
+    }
 
-        fn parse_body<'src>(input: &mut Input<'src>) -> Result<Vec<Item<'src>>, syn::Error> {
-            let mut body = Vec::new();
+    fn parse_raw_directive<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+        let position = input.position();
 
-            while !input.is_at_end() {
-                let position = input.position();
+        if !parse_statement_start(input) {
+            return Ok(None);
+        }
 
-                let item = parse_template_item(input)?
-                    .expect("parse_template_item should never return None");
-
-                match item {
-                    Item::KeywordStatement {
-                        keyword,
-                        statement: _,
-                        body: _,
-                    } if keyword.is_block_terminator() => {
-                        if keyword != Keyword::End {
-                            input.reset_to(position);
-                        }
-                        break;
-                    }
-                    _ => body.push(item),
+        input.consume_while(char::is_whitespace);
+
+        if input.consume_lit("raw").is_none() {
+            input.reset_to(position);
+            return Ok(None);
+        }
+
+        input.consume_while(char::is_whitespace);
+
+        if !parse_statement_end(input) {
+            input.reset_to(position);
+            return Ok(None);
+        }
+
+        let trim_start = input.requested_trim();
+        let mut parts = Vec::new();
+
+        loop {
+            parts.push(input.consume_until("<#"));
+
+            if input.is_at_end() {
+                return Err(input.error_at(position, "Unterminated raw block"));
+            }
+
+            let end_position = input.position();
+            if let Some(trim_end) = parse_raw_end(input) {
+                let mut content = input.combine(&parts).into_cow();
+
+                match trim_start {
+                    Some(Trim::Whitespace) => content = slice(content, str::trim_start),
+                    Some(Trim::Newline) => content = slice(content, strip_newline),
+                    None => {}
                 }
+
+                if trim_end {
+                    content = slice(content, str::trim_end);
+                }
+
+                return Ok(Some(Item::Literal(content)));
+            }
+
+            input.reset_to(end_position);
+            parts.push(
+                input
+                    .consume_lit("<#")
+                    .expect("Should start with a statement"),
+            );
+        }
+
+        /// Parse the end tag of a raw block, returning whether whitespace
+        /// before it should be trimmed.
+        fn parse_raw_end(input: &mut Input<'_>) -> Option<bool> {
+            let trim_end = has_statement_trim_marker(input.remainder().strip_prefix("<#")?);
+
+            if !parse_statement_start(input) {
+                return None;
             }
 
-            Ok(body)
+            input.consume_while(char::is_whitespace);
+            input.consume_lit("end")?;
+            input.consume_while(char::is_whitespace);
+
+            parse_statement_end(input).then_some(trim_end)
+        }
+    }
+
+    fn parse_extends_directive<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+        let position = input.position();
+
+        if !parse_statement_start(input) {
+            return Ok(None);
+        }
+
+        input.consume_while(char::is_whitespace);
+
+        if input.consume_lit("extends").is_none()
+            || input.consume_while(char::is_whitespace).is_empty()
+        {
+            input.reset_to(position);
+            return Ok(None);
         }
+
+        let content = parse_statement_content(input, position)?;
+        let path = syn::parse_str::<syn::LitStr>(content.as_ref()).map_err(|_| {
+            input.error_at(
+                position,
+                "Expected a string literal with the path of the base template",
+            )
+        })?;
+
+        Ok(Some(Item::Extends(
+            input.code(Cow::Owned(path.value()), position),
+        )))
+    }
+
+    fn parse_block_directive<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+        let position = input.position();
+
+        if !parse_statement_start(input) {
+            return Ok(None);
+        }
+
+        input.consume_while(char::is_whitespace);
+
+        if input.consume_lit("block").is_none()
+            || input.consume_while(char::is_whitespace).is_empty()
+        {
+            input.reset_to(position);
+            return Ok(None);
+        }
+
+        let Some(name) = parse_rust_identifier(input).map(Offset::into_cow) else {
+            return Err(input.error("Expected a block name"));
+        };
+
+        input.consume_while(char::is_whitespace);
+
+        if !parse_statement_end(input) {
+            return Err(input.error("Unterminated block statement"));
+        }
+
+        let body = parse_body(input)?;
+
+        Ok(Some(Item::Block { name, body }))
+    }
+
+    fn parse_match_arms<'src>(input: &mut Input<'src>) -> Result<Vec<Item<'src>>, syn::Error> {
+        let mut arms = Vec::new();
+
+        loop {
+            input.consume_while(char::is_whitespace);
+
+            let position = input.position();
+
+            let Some(item) = parse_template_item(input)? else {
+                return Err(input.error("Unterminated match statement"));
+            };
+
+            match item {
+                Item::KeywordStatement {
+                    keyword: Keyword::Case,
+                    ..
+                } => arms.push(item),
+                Item::KeywordStatement {
+                    keyword: Keyword::End,
+                    ..
+                } => break,
+                _ => {
+                    return Err(
+                        input.error_at(position, "Expected case statement in match statement")
+                    )
+                }
+            }
+        }
+
+        Ok(arms)
+    }
+
+    fn trim_case_arrow(pattern: Code<'_>) -> Code<'_> {
+        pattern.map(|pattern| match pattern {
+            Cow::Borrowed(value) => Cow::Borrowed(value.trim_end_matches("=>").trim_end()),
+            Cow::Owned(value) => Cow::Owned(value.trim_end_matches("=>").trim_end().to_owned()),
+        })
+    }
+
+    fn parse_body<'src>(input: &mut Input<'src>) -> Result<Vec<Item<'src>>, syn::Error> {
+        parse_body_until(input, Keyword::is_block_terminator, |keyword| {
+            keyword == Keyword::End
+        })
+    }
+
+    /// Parse a case body up to the next case or the end of the match
+    /// statement, neither of which is consumed. Else branches of statements
+    /// in the body are parsed as their siblings, like everywhere else.
+    fn parse_case_body<'src>(input: &mut Input<'src>) -> Result<Vec<Item<'src>>, syn::Error> {
+        parse_body_until(
+            input,
+            |keyword| matches!(keyword, Keyword::Case | Keyword::End),
+            |_| false,
+        )
+    }
+
+    /// Parse a statement body up to the next keyword for which `is_terminator`
+    /// returns true, which is consumed if `consume_terminator` returns true.
+    fn parse_body_until<'src>(
+        input: &mut Input<'src>,
+        is_terminator: impl Fn(Keyword) -> bool,
+        consume_terminator: impl Fn(Keyword) -> bool,
+    ) -> Result<Vec<Item<'src>>, syn::Error> {
+        let mut body = Vec::new();
+
+        while !input.is_at_end() {
+            let position = input.position();
+
+            let item =
+                parse_template_item(input)?.expect("parse_template_item should never return None");
+
+            match item {
+                Item::KeywordStatement { keyword, .. } if is_terminator(keyword) => {
+                    if !consume_terminator(keyword) {
+                        input.reset_to(position);
+                    }
+                    break;
+                }
+                _ => body.push(item),
+            }
+        }
+
+        Ok(body)
     }
 
     fn parse_plain_statement<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
-        if input.consume_lit("<#").is_none() {
+        let position = input.position();
+
+        if !parse_statement_start(input) {
             return Ok(None);
         }
 
-        let content = parse_statement_content(input)?;
+        let content = parse_statement_content(input, position)?;
 
         Ok(Some(Item::PlainStatement(content)))
     }
 
+    /// Parse the remaining content of the statement starting at `start`.
     fn parse_statement_content<'src>(
         input: &mut Input<'src>,
-    ) -> Result<Cow<'src, str>, syn::Error> {
-        let mut content = Cow::<'src, str>::Borrowed("");
+        start: Position,
+    ) -> Result<Code<'src>, syn::Error> {
+        input.consume_while(char::is_whitespace);
 
-        loop {
-            let part = input.consume_until_any("#%");
+        let position = input.position();
 
-            append(&mut content, part.into_str());
+        // Statements may open or close blocks, so brackets are not balanced
+        let Some(content) = parse_rust_code(input, "#>", false) else {
+            return Err(input.error_at(start, "Unterminated statement"));
+        };
 
-            if input.consume_lit("%#>").is_some() {
-                append(&mut content, "#>");
-            } else if input.consume_lit("#>").is_some() {
-                break;
-            } else if input.consume_lit("#").is_some() {
-                append(&mut content, "#");
-            } else if input.consume_lit("%").is_some() {
-                append(&mut content, "%");
-            } else {
-                return Err(syn::Error::new(Span::call_site(), "Unterminated statement"));
-            }
+        let mut content = trim(content);
+
+        // Rust statements never end with a minus, so it is always a trim marker
+        let trim_after = content.ends_with('-');
+        if trim_after {
+            content = trim(slice(content, |c| &c[..c.len() - 1]));
+        }
+
+        request_trim_after_statement(input, trim_after);
+
+        Ok(input.code(content, position))
+    }
+
+    /// Parse the start of a statement tag including an optional trim marker.
+    fn parse_statement_start(input: &mut Input<'_>) -> bool {
+        if input.consume_lit("<#").is_none() {
+            return false;
+        }
+
+        if has_statement_trim_marker(input.remainder()) {
+            input.consume_lit("-");
+        }
+
+        true
+    }
+
+    /// Parse the end of a shorthand statement tag including an optional trim marker.
+    fn parse_statement_end(input: &mut Input<'_>) -> bool {
+        if input
+            .consume_lit("->")
+            .or_else(|| input.consume_lit("-#>"))
+            .is_some()
+        {
+            request_trim_after_statement(input, true);
+            return true;
+        }
+
+        if input
+            .consume_lit(">")
+            .or_else(|| input.consume_lit("#>"))
+            .is_some()
+        {
+            request_trim_after_statement(input, false);
+            return true;
         }
 
-        Ok(trim(content))
+        false
     }
 }
 
 fn parse_comment<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
-    let Some(start) = input.consume_lit("<!--") else {
-        return Ok(None);
-    };
+    return select2(input, (parse_template_comment, parse_html_comment));
+
+    fn parse_template_comment<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+        let position = input.position();
 
-    let content = input.consume_until("-->");
+        if input.consume_lit("<#--").is_none() {
+            return Ok(None);
+        }
 
-    let Some(end) = input.consume_lit("-->") else {
-        return Err(syn::Error::new(Span::call_site(), "Unterminated comment"));
-    };
+        input.consume_until("--#>");
+
+        if input.consume_lit("--#>").is_none() {
+            return Err(input.error_at(position, "Unterminated comment"));
+        }
+
+        request_trim_after_statement(input, false);
+
+        parse_next_item(input)
+    }
+
+    fn parse_html_comment<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+        let position = input.position();
+
+        let Some(start) = input.consume_lit("<!--") else {
+            return Ok(None);
+        };
+
+        let content = input.consume_until("-->");
+
+        let Some(end) = input.consume_lit("-->") else {
+            return Err(input.error_at(position, "Unterminated comment"));
+        };
+
+        if input.options().strip_html_comments && !is_conditional_comment(&content) {
+            return parse_next_item(input);
+        }
+
+        let comment = input.combine(&[start, content, end]);
 
-    let comment = input.combine(&[start, content, end]);
+        Ok(Some(Item::Literal(comment.into_cow())))
+    }
 
-    Ok(Some(Item::Literal(comment.into_cow())))
+    /// Conditional comments like `<!--[if IE]>...<![endif]-->` are interpreted
+    /// by some browsers and must be kept.
+    fn is_conditional_comment(content: &str) -> bool {
+        content.starts_with("[if") || content.starts_with("<![endif]")
+    }
 }
 
 fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
@@ -299,23 +699,21 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
 
     input.consume_while(char::is_whitespace);
 
-    let children = if input.consume_lit("/>").is_some() {
+    let (children, slots) = if input.consume_lit("/>").is_some() {
         // Collapsed tag
-        Vec::new()
+        (Vec::new(), Vec::new())
     } else if input.consume_lit(">").is_some() {
         // Regular tag with body - parse_template_children also parses the end tag
-        parse_template_children(input, name.as_ref())?
+        parse_template_children(input, name.as_ref(), position)?
     } else {
-        return Err(syn::Error::new(
-            Span::call_site(),
-            "Unterminated template reference",
-        ));
+        return Err(input.error("Unterminated template reference"));
     };
 
     return Ok(Some(Item::ChildTemplate {
         name,
         arguments,
         children,
+        slots,
     }));
 
     fn parse_template_argument<'src>(
@@ -363,24 +761,30 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
                 return Ok(value);
             }
 
-            return Err(syn::Error::new(
-                Span::call_site(),
-                "Unexpected token for template argument value",
-            ));
+            return Err(input.error("Unexpected token for template argument value"));
 
             fn parse_expression_value<'src>(
                 input: &mut Input<'src>,
             ) -> Result<Option<TemplateArgumentValue<'src>>, syn::Error> {
-                let Some(Item::Expression(expr)) = parse_expression(input)? else {
-                    return Ok(None);
-                };
+                let position = input.position();
 
-                Ok(Some(TemplateArgumentValue::Expression(expr)))
+                match parse_expression(input)? {
+                    Some(Item::Expression(expr, _)) => {
+                        Ok(Some(TemplateArgumentValue::Expression(expr)))
+                    }
+                    Some(Item::UnescapedExpression(_)) => Err(input.error_at(
+                        position,
+                        "Unescaped expressions cannot be used as template arguments",
+                    )),
+                    _ => Ok(None),
+                }
             }
 
             fn parse_double_ticks_value<'src>(
                 input: &mut Input<'src>,
             ) -> Result<Option<TemplateArgumentValue<'src>>, syn::Error> {
+                let position = input.position();
+
                 if input.consume_lit("\"").is_none() {
                     return Ok(None);
                 }
@@ -397,10 +801,7 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
                     } else if input.consume_lit("\"").is_some() {
                         break;
                     } else {
-                        return Err(syn::Error::new(
-                            Span::call_site(),
-                            "Unterminated string literal",
-                        ));
+                        return Err(input.error_at(position, "Unterminated string literal"));
                     }
                 }
 
@@ -410,6 +811,8 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
             fn parse_single_ticks_value<'src>(
                 input: &mut Input<'src>,
             ) -> Result<Option<TemplateArgumentValue<'src>>, syn::Error> {
+                let position = input.position();
+
                 if input.consume_lit("'").is_none() {
                     return Ok(None);
                 }
@@ -426,10 +829,7 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
                     } else if input.consume_lit("'").is_some() {
                         break;
                     } else {
-                        return Err(syn::Error::new(
-                            Span::call_site(),
-                            "Unterminated string literal",
-                        ));
+                        return Err(input.error_at(position, "Unterminated string literal"));
                     }
                 }
 
@@ -441,12 +841,27 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
     fn parse_template_children<'src>(
         input: &mut Input<'src>,
         name: &str,
-    ) -> Result<Vec<Item<'src>>, syn::Error> {
+        start: Position,
+    ) -> Result<(Vec<Item<'src>>, Vec<TemplateSlot<'src>>), syn::Error> {
         let mut children = Vec::new();
+        let mut slots = Vec::<TemplateSlot>::new();
 
         while !input.is_at_end() {
             if parse_end_tag(input, name)? {
-                return Ok(children);
+                return Ok((children, slots));
+            }
+
+            let position = input.position();
+
+            if let Some(slot) = parse_slot(input)? {
+                if slots.iter().any(|s| s.name == slot.name) {
+                    return Err(
+                        input.error_at(position, &format!("Duplicate slot `{}`", slot.name))
+                    );
+                }
+
+                slots.push(slot);
+                continue;
             }
 
             let Some(item) = parse_template_item(input)? else {
@@ -456,10 +871,49 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
             children.push(item);
         }
 
-        return Err(syn::Error::new(
-            Span::call_site(),
-            "Unterminated template reference",
-        ));
+        return Err(input.error_at(start, "Unterminated template reference"));
+
+        fn parse_slot<'src>(
+            input: &mut Input<'src>,
+        ) -> Result<Option<TemplateSlot<'src>>, syn::Error> {
+            let position = input.position();
+
+            if input.consume_lit("<:").is_none() {
+                return Ok(None);
+            }
+
+            let Some(name) = parse_rust_identifier(input) else {
+                input.reset_to(position);
+                return Ok(None);
+            };
+
+            input.consume_while(char::is_whitespace);
+
+            if input.consume_lit(">").is_none() {
+                input.reset_to(position);
+                return Ok(None);
+            }
+
+            let end_tag = format!(":{}", name.as_ref());
+            let mut children = Vec::new();
+
+            while !input.is_at_end() {
+                if parse_end_tag(input, &end_tag)? {
+                    return Ok(Some(TemplateSlot {
+                        name: name.into_cow(),
+                        children,
+                    }));
+                }
+
+                let Some(item) = parse_template_item(input)? else {
+                    break;
+                };
+
+                children.push(item);
+            }
+
+            Err(input.error_at(position, "Unterminated slot"))
+        }
 
         fn parse_end_tag(input: &mut Input<'_>, name: &str) -> Result<bool, syn::Error> {
             let position = input.position();
@@ -488,15 +942,81 @@ fn parse_child_template<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
 }
 
 fn parse_literal<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+    let trim_start = input.requested_trim();
+
     let first = input
         .consume_count(1)
         .expect("This method must not be called with an empty input");
 
     let rest = input.consume_until_any("<{%");
 
-    let combined = input.combine(&[first, rest]).into_cow();
+    let mut literal = input.combine(&[first, rest]).into_cow();
+
+    match trim_start {
+        Some(Trim::Whitespace) => literal = slice(literal, str::trim_start),
+        Some(Trim::Newline) => literal = slice(literal, strip_newline),
+        None => {}
+    }
+
+    let remainder = input.remainder();
+    let trim_end = remainder
+        .strip_prefix("{{")
+        .is_some_and(has_expression_trim_marker)
+        || remainder
+            .strip_prefix("<#")
+            .is_some_and(has_statement_trim_marker);
+
+    if trim_end {
+        literal = slice(literal, str::trim_end);
+    }
+
+    if literal.is_empty() {
+        // Nothing left after trimming, continue with the next item instead
+        return parse_next_item(input);
+    }
+
+    Ok(Some(Item::Literal(literal)))
+}
+
+/// Parse the item following one that produced no output.
+fn parse_next_item<'src>(input: &mut Input<'src>) -> ParseResult<'src> {
+    if input.is_at_end() {
+        return Ok(Some(Item::Literal(Cow::Borrowed(""))));
+    }
+
+    parse_template_item(input)
+}
+
+fn request_trim_after_statement(input: &mut Input<'_>, trim_whitespace: bool) {
+    if trim_whitespace {
+        input.trim_here(Trim::Whitespace);
+    } else if input.options().trim_blocks {
+        input.trim_here(Trim::Newline);
+    }
+}
+
+/// Checks for a `!` marker at the start of an expression, rendering it without
+/// escaping. The marker must be followed by whitespace to distinguish it from
+/// a negation.
+fn has_unescaped_marker(content: &str) -> bool {
+    content
+        .strip_prefix('!')
+        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
+
+/// Checks for a `-` marker at the start of an expression, requesting whitespace
+/// before it to be trimmed. The marker must be followed by whitespace to
+/// distinguish it from a negation.
+fn has_expression_trim_marker(content: &str) -> bool {
+    content
+        .strip_prefix('-')
+        .is_some_and(|rest| rest.starts_with(char::is_whitespace))
+}
 
-    Ok(Some(Item::Literal(combined)))
+/// Checks for a `-` marker at the start of a statement, requesting whitespace
+/// before it to be trimmed.
+fn has_statement_trim_marker(content: &str) -> bool {
+    content.starts_with('-') && !content.starts_with("--")
 }
 
 fn append<'src>(content: &mut Cow<'src, str>, part: &'src str) {
@@ -508,9 +1028,20 @@ fn append<'src>(content: &mut Cow<'src, str>, part: &'src str) {
 }
 
 fn trim(content: Cow<'_, str>) -> Cow<'_, str> {
+    slice(content, str::trim)
+}
+
+fn strip_newline(content: &str) -> &str {
+    content
+        .strip_prefix("\r\n")
+        .or_else(|| content.strip_prefix('\n'))
+        .unwrap_or(content)
+}
+
+fn slice<'src>(content: Cow<'src, str>, f: impl for<'a> Fn(&'a str) -> &'a str) -> Cow<'src, str> {
     match content {
-        Cow::Borrowed(value) => Cow::Borrowed(value.trim()),
-        Cow::Owned(value) => Cow::Owned(value.trim().to_owned()),
+        Cow::Borrowed(value) => Cow::Borrowed(f(value)),
+        Cow::Owned(value) => Cow::Owned(f(&value).to_owned()),
     }
 }
 
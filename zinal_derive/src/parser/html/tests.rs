@@ -1,6 +1,9 @@
 use std::borrow::Cow;
 
-use crate::parser::{input::Input, Item, Keyword, TemplateArgument, TemplateArgumentValue};
+use crate::parser::{
+    input::Input, Code, EscapeContext, Item, Keyword, Origin, ParseOptions, TemplateArgument,
+    TemplateArgumentValue, TemplateSlot,
+};
 
 use super::HtmlParser;
 
@@ -45,9 +48,10 @@ fn expression() {
     assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
     assert_eq!(
         result.unwrap(),
-        vec![Item::Expression(Cow::from(
-            "self.name.to_ascii_uppercase()"
-        ))]
+        vec![Item::Expression(
+            Code::from("self.name.to_ascii_uppercase()"),
+            EscapeContext::Html
+        )]
     );
 }
 
@@ -61,7 +65,46 @@ fn expression_with_escape_and_whitespace() {
     assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
     assert_eq!(
         result.unwrap(),
-        vec![Item::Expression(Cow::from("format!(\"{{ {} }}\", foo)"))]
+        vec![Item::Expression(
+            Code::from("format!(\"{{ {} }}\", foo)"),
+            EscapeContext::Html
+        )]
+    );
+}
+
+#[test]
+fn expression_with_nested_braces() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("{{ format!(\"{}}}\", Point { x: '}' }) }}");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![Item::Expression(
+            Code::from("format!(\"{}}}\", Point { x: '}' })"),
+            EscapeContext::Html
+        )]
+    );
+}
+
+#[test]
+fn expression_with_trim_markers() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<p>\n  {{- self.name -}}\n</p>{{ -1 }}");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::Literal(Cow::from("<p>")),
+            Item::Expression(Code::from("self.name"), EscapeContext::Html),
+            Item::Literal(Cow::from("</p>")),
+            Item::Expression(Code::from("-1"), EscapeContext::Html),
+        ]
     );
 }
 
@@ -87,7 +130,10 @@ fn literal_with_expression() {
         result.unwrap(),
         vec![
             Item::Literal(Cow::from("<div>")),
-            Item::Expression(Cow::from("self.name.to_ascii_uppercase()")),
+            Item::Expression(
+                Code::from("self.name.to_ascii_uppercase()"),
+                EscapeContext::Html
+            ),
             Item::Literal(Cow::from("</div>"))
         ]
     );
@@ -104,7 +150,7 @@ fn expression_then_literal() {
     assert_eq!(
         result.unwrap(),
         vec![
-            Item::Expression(Cow::from("self.name")),
+            Item::Expression(Code::from("self.name"), EscapeContext::Html),
             Item::Literal(Cow::from(" is here"))
         ]
     );
@@ -120,7 +166,7 @@ fn plain_statement() {
     assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
     assert_eq!(
         result.unwrap(),
-        vec![Item::PlainStatement(Cow::from(
+        vec![Item::PlainStatement(Code::from(
             "println!(\"Hello, {}\", self.name)"
         ))]
     );
@@ -136,12 +182,144 @@ fn plain_statement_with_escape() {
     assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
     assert_eq!(
         result.unwrap(),
-        vec![Item::PlainStatement(Cow::from(
+        vec![Item::PlainStatement(Code::from(
             "println!(\"Hello, <# Nested #>\")"
         ))]
     );
 }
 
+#[test]
+fn plain_statement_with_terminator_in_string() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<# println!(r\"#>\"); #>");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![Item::PlainStatement(Code::from("println!(r\"#>\");"))]
+    );
+}
+
+#[test]
+fn statement_with_trim_markers() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<ul>\n<#- for x in xs -#>\n  <li>{{x}}</li>\n<#- end -#>\n</ul>");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::Literal(Cow::from("<ul>")),
+            Item::KeywordStatement {
+                keyword: Keyword::For,
+                label: None,
+                statement: Some(Code::from("x in xs")),
+                body: vec![
+                    Item::Literal(Cow::from("<li>")),
+                    Item::Expression(Code::from("x"), EscapeContext::Html),
+                    Item::Literal(Cow::from("</li>")),
+                ]
+            },
+            Item::Literal(Cow::from("</ul>")),
+        ]
+    );
+}
+
+#[test]
+fn statement_with_trim_blocks() {
+    let mut parser = HtmlParser;
+
+    let options = ParseOptions {
+        trim_blocks: true,
+        ..Default::default()
+    };
+    let input = Input::configured("<#if a#>\n  yes\n<#end#>\n\nno", Origin::default(), options);
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::KeywordStatement {
+                keyword: Keyword::If,
+                label: None,
+                statement: Some(Code::from("a")),
+                body: vec![Item::Literal(Cow::from("  yes\n"))]
+            },
+            Item::Literal(Cow::from("\nno")),
+        ]
+    );
+}
+
+#[test]
+fn unescaped_expression() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("{{! self.html -}} {{!self.hidden}}");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::UnescapedExpression(Code::from("self.html")),
+            Item::Expression(Code::from("!self.hidden"), EscapeContext::Html),
+        ]
+    );
+}
+
+#[test]
+fn unescaped_expression_as_template_argument() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<Child body={{! self.html }} />");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn raw_block() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#raw#><p>{{ message }}</p><Comp /><# x #><#end#>{{x}}");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::Literal(Cow::from("<p>{{ message }}</p><Comp /><# x #>")),
+            Item::Expression(Code::from("x"), EscapeContext::Html),
+        ]
+    );
+}
+
+#[test]
+fn raw_block_with_trim_markers() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#- raw -#>\n  {{ a }}\n<#- end>");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(result.unwrap(), vec![Item::Literal(Cow::from("{{ a }}"))]);
+}
+
+#[test]
+fn raw_block_unterminated() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#raw#>{{ a }}<#end");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
 #[test]
 fn keyword_statement_shorthand() {
     let mut parser = HtmlParser;
@@ -154,6 +332,7 @@ fn keyword_statement_shorthand() {
         result.unwrap(),
         vec![Item::KeywordStatement {
             keyword: Keyword::Break,
+            label: None,
             statement: None,
             body: Vec::new()
         }]
@@ -172,12 +351,98 @@ fn keyword_statement_longform() {
         result.unwrap(),
         vec![Item::KeywordStatement {
             keyword: Keyword::Break,
-            statement: Some(Cow::Borrowed("'outer")),
+            label: None,
+            statement: Some(Code::from("'outer")),
+            body: Vec::new()
+        }]
+    );
+}
+
+#[test]
+fn keyword_statement_let() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#let total = self.items.len()#>{{total}}");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::KeywordStatement {
+                keyword: Keyword::Let,
+                label: None,
+                statement: Some(Code::from("total = self.items.len()")),
+                body: Vec::new()
+            },
+            Item::Expression(Code::from("total"), EscapeContext::Html)
+        ]
+    );
+}
+
+#[test]
+fn keyword_statement_let_without_pattern() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#let>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn keyword_statement_continue() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#continue#>");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![Item::KeywordStatement {
+            keyword: Keyword::Continue,
+            label: None,
+            statement: None,
             body: Vec::new()
         }]
     );
 }
 
+#[test]
+fn block_statement_labeled_loop() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<# 'outer: loop #><#break 'outer#><#end>");
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![Item::KeywordStatement {
+            keyword: Keyword::Loop,
+            label: Some(Cow::from("'outer")),
+            statement: None,
+            body: vec![Item::KeywordStatement {
+                keyword: Keyword::Break,
+                label: None,
+                statement: Some(Code::from("'outer")),
+                body: Vec::new()
+            }]
+        }]
+    );
+}
+
+#[test]
+fn block_statement_labeled_if() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#'outer: if true#><#end>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
 #[test]
 fn block_statement_for() {
     let mut parser = HtmlParser;
@@ -190,10 +455,11 @@ fn block_statement_for() {
         result.unwrap(),
         vec![Item::KeywordStatement {
             keyword: Keyword::For,
-            statement: Some(Cow::from("name in self.names")),
+            label: None,
+            statement: Some(Code::from("name in self.names")),
             body: vec![
                 Item::Literal(Cow::from("Hello, ")),
-                Item::Expression(Cow::from("name"))
+                Item::Expression(Code::from("name"), EscapeContext::Html)
             ]
         }]
     );
@@ -213,16 +479,19 @@ fn block_statement_if() {
         vec![
             Item::KeywordStatement {
                 keyword: Keyword::If,
-                statement: Some(Cow::from("age > 18")),
+                label: None,
+                statement: Some(Code::from("age > 18")),
                 body: vec![Item::Literal(Cow::from("Over 18")),]
             },
             Item::KeywordStatement {
                 keyword: Keyword::ElseIf,
-                statement: Some(Cow::from("age < 18")),
+                label: None,
+                statement: Some(Code::from("age < 18")),
                 body: vec![Item::Literal(Cow::from("Under 18")),]
             },
             Item::KeywordStatement {
                 keyword: Keyword::Else,
+                label: None,
                 statement: None,
                 body: vec![Item::Literal(Cow::from("Exactly 18")),]
             }
@@ -230,6 +499,63 @@ fn block_statement_if() {
     );
 }
 
+#[test]
+fn block_statement_match() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new(
+        "<#match self.state#>\n  <#case State::Loading => #>Loading\n  <#case State::Done(n)#>{{n}}<#end>!",
+    );
+    let result = parser.parse(input);
+
+    assert!(result.is_ok(), "Error in result: {:?}", result.unwrap_err());
+    assert_eq!(
+        result.unwrap(),
+        vec![
+            Item::KeywordStatement {
+                keyword: Keyword::Match,
+                label: None,
+                statement: Some(Code::from("self.state")),
+                body: vec![
+                    Item::KeywordStatement {
+                        keyword: Keyword::Case,
+                        label: None,
+                        statement: Some(Code::from("State::Loading")),
+                        body: vec![Item::Literal(Cow::from("Loading\n  "))]
+                    },
+                    Item::KeywordStatement {
+                        keyword: Keyword::Case,
+                        label: None,
+                        statement: Some(Code::from("State::Done(n)")),
+                        body: vec![Item::Expression(Code::from("n"), EscapeContext::Html)]
+                    }
+                ]
+            },
+            Item::Literal(Cow::from("!"))
+        ]
+    );
+}
+
+#[test]
+fn block_statement_match_without_case() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#match self.state#>Content<#end>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn block_statement_case_without_pattern() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#match self.state#><#case>Content<#end>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
 #[test]
 fn comment() {
     let mut parser = HtmlParser;
@@ -244,6 +570,58 @@ fn comment() {
     );
 }
 
+#[test]
+fn template_comment() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("a<#-- Note {{expr}} <#end> <!-- x --> --#>b<#-- --#>");
+
+    let result = parser.parse(input).expect("Should have parsed");
+
+    assert_eq!(
+        result,
+        vec![
+            Item::Literal(Cow::from("a")),
+            Item::Literal(Cow::from("b")),
+            Item::Literal(Cow::from("")),
+        ]
+    );
+}
+
+#[test]
+fn template_comment_unterminated() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#-- Note #>");
+
+    assert!(parser.parse(input).is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn comment_stripped() {
+    let mut parser = HtmlParser;
+
+    let options = ParseOptions {
+        strip_html_comments: true,
+        ..Default::default()
+    };
+    let input = Input::configured(
+        "<!-- Note -->a<!--[if IE]><p>IE</p><![endif]-->",
+        Origin::default(),
+        options,
+    );
+
+    let result = parser.parse(input).expect("Should have parsed");
+
+    assert_eq!(
+        result,
+        vec![
+            Item::Literal(Cow::from("a")),
+            Item::Literal(Cow::from("<!--[if IE]><p>IE</p><![endif]-->")),
+        ]
+    );
+}
+
 #[test]
 fn child_template_minimal() {
     let mut parser = HtmlParser;
@@ -257,7 +635,8 @@ fn child_template_minimal() {
         vec![Item::ChildTemplate {
             name: Cow::from("::foo::Bar"),
             arguments: vec![],
-            children: vec![]
+            children: vec![],
+            slots: vec![]
         }]
     );
 }
@@ -278,7 +657,7 @@ fn child_template_with_args() {
             arguments: vec![
                 TemplateArgument {
                     name: Cow::from("expr"),
-                    value: TemplateArgumentValue::Expression(Cow::from("self.name"))
+                    value: TemplateArgumentValue::Expression(Code::from("self.name"))
                 },
                 TemplateArgument {
                     name: Cow::from("lit_double"),
@@ -301,7 +680,8 @@ fn child_template_with_args() {
                     value: TemplateArgumentValue::BoolLiteral(false)
                 }
             ],
-            children: vec![]
+            children: vec![],
+            slots: vec![]
         }]
     );
 }
@@ -319,9 +699,150 @@ fn child_template_with_args_and_body() {
             name: Cow::from("Child"),
             arguments: vec![TemplateArgument {
                 name: Cow::from("expr"),
-                value: TemplateArgumentValue::Expression(Cow::from("self.name"))
+                value: TemplateArgumentValue::Expression(Code::from("self.name"))
             },],
-            children: vec![Item::Literal(Cow::from("Hello, World!"))]
+            children: vec![Item::Literal(Cow::from("Hello, World!"))],
+            slots: vec![]
         }]
     );
 }
+
+#[test]
+fn child_template_with_slots() {
+    let mut parser = HtmlParser;
+
+    let input =
+        Input::new("<Card><:header>Title</:header>Body<:footer>{{self.name}}</:footer></Card>");
+    let result = parser.parse(input).expect("Should have parsed");
+
+    assert_eq!(
+        result,
+        vec![Item::ChildTemplate {
+            name: Cow::from("Card"),
+            arguments: vec![],
+            children: vec![Item::Literal(Cow::from("Body"))],
+            slots: vec![
+                TemplateSlot {
+                    name: Cow::from("header"),
+                    children: vec![Item::Literal(Cow::from("Title"))]
+                },
+                TemplateSlot {
+                    name: Cow::from("footer"),
+                    children: vec![Item::Expression(
+                        Code::from("self.name"),
+                        EscapeContext::Html
+                    )]
+                }
+            ]
+        }]
+    );
+}
+
+#[test]
+fn child_template_with_duplicate_slot() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<Card><:header>One</:header><:header>Two</:header></Card>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn child_template_with_unterminated_slot() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<Card><:header>Title</Card>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn slot_reference() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("{{@slot header}}{{ @slot? footer }}");
+    let result = parser.parse(input).expect("Should have parsed");
+
+    assert_eq!(
+        result,
+        vec![
+            Item::Slot {
+                name: Cow::from("header"),
+                optional: false
+            },
+            Item::Slot {
+                name: Cow::from("footer"),
+                optional: true
+            }
+        ]
+    );
+}
+
+#[test]
+fn extends_directive() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#extends \"layouts/base.html\"#>");
+    let result = parser.parse(input).expect("Should have parsed");
+
+    assert_eq!(result, vec![Item::Extends(Code::from("layouts/base.html"))]);
+}
+
+#[test]
+fn extends_directive_without_path() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#extends base #>");
+    let result = parser.parse(input);
+
+    assert!(result.is_err(), "Unexpectedly succeeded");
+}
+
+#[test]
+fn extends_with_content_outside_blocks() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#extends \"base.html\"#>\n<#block title#><#end#>\nStray content");
+    let error = parser.parse(input).expect_err("Should not parse");
+
+    assert!(error.to_string().starts_with(
+        "Templates extending a base template must only contain blocks\n --> inline template:3:1\n"
+    ));
+}
+
+#[test]
+fn extends_twice() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<#extends \"a.html\"#><#extends \"b.html\"#>");
+    let error = parser.parse(input).expect_err("Should not parse");
+
+    assert!(error
+        .to_string()
+        .starts_with("A template can only extend one base template\n --> inline template:1:21\n"));
+}
+
+#[test]
+fn block_directive() {
+    let mut parser = HtmlParser;
+
+    let input = Input::new("<title><# block title #>Hello, {{self.name}}<#end></title>");
+    let result = parser.parse(input).expect("Should have parsed");
+
+    assert_eq!(
+        result,
+        vec![
+            Item::Literal(Cow::from("<title>")),
+            Item::Block {
+                name: Cow::from("title"),
+                body: vec![
+                    Item::Literal(Cow::from("Hello, ")),
+                    Item::Expression(Code::from("self.name"), EscapeContext::Html)
+                ]
+            },
+            Item::Literal(Cow::from("</title>"))
+        ]
+    );
+}
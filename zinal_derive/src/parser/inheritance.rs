@@ -0,0 +1,227 @@
+use std::{borrow::Cow, collections::HashMap};
+
+use super::{Code, Item, TemplateSlot};
+
+/// Returns the path of the base template if the template extends another
+/// template. The parser makes sure there is at most one.
+pub fn base_template<'a, 'src>(items: &'a [Item<'src>]) -> Option<&'a Code<'src>> {
+    items.iter().find_map(|item| match item {
+        Item::Extends(path) => Some(path),
+        _ => None,
+    })
+}
+
+/// Merge a chain of templates into a single template.
+///
+/// The chain starts with the most derived template, followed by its base
+/// template and so on. The last template in the chain is the root template,
+/// which determines the structure of the output. Blocks of the root template
+/// are replaced by the blocks with the same name of the most derived template
+/// overriding them. The parser makes sure that templates extending a base
+/// template only contain blocks.
+pub fn resolve_inheritance<'src>(mut chain: Vec<Vec<Item<'src>>>) -> Vec<Item<'src>> {
+    let root = chain.pop().expect("chain must not be empty");
+
+    let mut overrides = HashMap::new();
+    for template in chain {
+        for item in template {
+            if let Item::Block { name, body } = item {
+                collect_blocks(&body, &mut overrides);
+                overrides.entry(name).or_insert(body);
+            }
+        }
+    }
+
+    apply_overrides(root, &overrides, &mut Vec::new())
+}
+
+fn collect_blocks<'src>(
+    items: &[Item<'src>],
+    overrides: &mut HashMap<Cow<'src, str>, Vec<Item<'src>>>,
+) {
+    for item in items {
+        if let Item::Block { name, body } = item {
+            collect_blocks(body, overrides);
+            overrides
+                .entry(name.clone())
+                .or_insert_with(|| body.clone());
+        }
+    }
+}
+
+fn apply_overrides<'src>(
+    items: Vec<Item<'src>>,
+    overrides: &HashMap<Cow<'src, str>, Vec<Item<'src>>>,
+    active: &mut Vec<Cow<'src, str>>,
+) -> Vec<Item<'src>> {
+    items
+        .into_iter()
+        .filter(|item| !matches!(item, Item::Extends(_)))
+        .map(|item| match item {
+            Item::Block { name, body } => {
+                // Blocks are not replaced within their own replacement
+                let body = match overrides.get(&name) {
+                    Some(body) if !active.contains(&name) => body.clone(),
+                    _ => body,
+                };
+
+                active.push(name.clone());
+                let body = apply_overrides(body, overrides, active);
+                active.pop();
+
+                Item::Block { name, body }
+            }
+            Item::KeywordStatement {
+                keyword,
+                label,
+                statement,
+                body,
+            } => Item::KeywordStatement {
+                keyword,
+                label,
+                statement,
+                body: apply_overrides(body, overrides, active),
+            },
+            Item::ChildTemplate {
+                name,
+                arguments,
+                children,
+                slots,
+            } => Item::ChildTemplate {
+                name,
+                arguments,
+                children: apply_overrides(children, overrides, active),
+                slots: slots
+                    .into_iter()
+                    .map(|slot| TemplateSlot {
+                        name: slot.name,
+                        children: apply_overrides(slot.children, overrides, active),
+                    })
+                    .collect(),
+            },
+            item => item,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use crate::parser::{Code, EscapeContext, Item, Keyword};
+
+    use super::resolve_inheritance;
+
+    #[test]
+    fn block_overridden() {
+        let base = vec![
+            Item::Literal(Cow::from("<title>")),
+            Item::Block {
+                name: Cow::from("title"),
+                body: vec![Item::Literal(Cow::from("Default"))],
+            },
+            Item::Literal(Cow::from("</title>")),
+        ];
+        let child = vec![
+            Item::Extends(Code::from("base.html")),
+            Item::Literal(Cow::from("\n")),
+            Item::Block {
+                name: Cow::from("title"),
+                body: vec![Item::Expression(
+                    Code::from("self.title"),
+                    EscapeContext::Html,
+                )],
+            },
+        ];
+
+        let result = resolve_inheritance(vec![child, base]);
+
+        assert_eq!(
+            result,
+            vec![
+                Item::Literal(Cow::from("<title>")),
+                Item::Block {
+                    name: Cow::from("title"),
+                    body: vec![Item::Expression(
+                        Code::from("self.title"),
+                        EscapeContext::Html
+                    )],
+                },
+                Item::Literal(Cow::from("</title>")),
+            ]
+        );
+    }
+
+    #[test]
+    fn block_default() {
+        let base = vec![Item::Block {
+            name: Cow::from("title"),
+            body: vec![Item::Literal(Cow::from("Default"))],
+        }];
+        let child = vec![Item::Extends(Code::from("base.html"))];
+
+        let result = resolve_inheritance(vec![child, base]);
+
+        assert_eq!(
+            result,
+            vec![Item::Block {
+                name: Cow::from("title"),
+                body: vec![Item::Literal(Cow::from("Default"))],
+            }]
+        );
+    }
+
+    #[test]
+    fn most_derived_block_wins() {
+        let base = vec![Item::KeywordStatement {
+            keyword: Keyword::If,
+            label: None,
+            statement: Some(Code::from("true")),
+            body: vec![Item::Block {
+                name: Cow::from("content"),
+                body: vec![],
+            }],
+        }];
+        let middle = vec![
+            Item::Extends(Code::from("base.html")),
+            Item::Block {
+                name: Cow::from("content"),
+                body: vec![
+                    Item::Literal(Cow::from("Middle")),
+                    Item::Block {
+                        name: Cow::from("inner"),
+                        body: vec![Item::Literal(Cow::from("Middle inner"))],
+                    },
+                ],
+            },
+        ];
+        let child = vec![
+            Item::Extends(Code::from("middle.html")),
+            Item::Block {
+                name: Cow::from("inner"),
+                body: vec![Item::Literal(Cow::from("Child inner"))],
+            },
+        ];
+
+        let result = resolve_inheritance(vec![child, middle, base]);
+
+        assert_eq!(
+            result,
+            vec![Item::KeywordStatement {
+                keyword: Keyword::If,
+                label: None,
+                statement: Some(Code::from("true")),
+                body: vec![Item::Block {
+                    name: Cow::from("content"),
+                    body: vec![
+                        Item::Literal(Cow::from("Middle")),
+                        Item::Block {
+                            name: Cow::from("inner"),
+                            body: vec![Item::Literal(Cow::from("Child inner"))],
+                        },
+                    ],
+                }],
+            }]
+        );
+    }
+}
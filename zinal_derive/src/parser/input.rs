@@ -1,9 +1,23 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, rc::Rc};
+
+use super::{Code, Origin, ParseOptions};
 
 pub struct Input<'src> {
     source: &'src str,
     remainder: &'src str,
     offset: usize,
+    origin: Rc<Origin>,
+    options: ParseOptions,
+    trim: Option<(usize, Trim)>,
+}
+
+/// Whitespace to remove from the start of a literal.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Trim {
+    /// Remove all whitespace
+    Whitespace,
+    /// Remove a single newline
+    Newline,
 }
 
 #[derive(Clone, Debug, PartialEq, Eq)]
@@ -16,18 +30,61 @@ pub struct Offset<'src> {
 pub struct Position(usize);
 
 impl<'src> Input<'src> {
+    #[cfg(test)]
     pub fn new(source: &'src str) -> Self {
+        Self::configured(source, Origin::default(), ParseOptions::default())
+    }
+
+    pub fn configured(source: &'src str, origin: Origin, options: ParseOptions) -> Self {
         Self {
             source,
             remainder: source,
             offset: 0,
+            origin: Rc::new(origin),
+            options,
+            trim: None,
         }
     }
 
+    pub fn options(&self) -> &ParseOptions {
+        &self.options
+    }
+
+    /// Request the literal starting at the current position to be trimmed.
+    pub fn trim_here(&mut self, trim: Trim) {
+        self.trim = Some((self.offset, trim));
+    }
+
+    /// The trimming requested for a literal starting at the current position.
+    pub fn requested_trim(&self) -> Option<Trim> {
+        self.trim
+            .filter(|(offset, _)| *offset == self.offset)
+            .map(|(_, trim)| trim)
+    }
+
+    /// Create embedded code starting at the given position.
+    pub fn code(&self, text: Cow<'src, str>, position: Position) -> Code<'src> {
+        Code::new(text, self.source, position.0, self.origin.clone())
+    }
+
+    /// Create an error located at the current position.
+    pub fn error(&self, message: &str) -> syn::Error {
+        self.error_at(self.position(), message)
+    }
+
+    /// Create an error located at the given position.
+    pub fn error_at(&self, position: Position, message: &str) -> syn::Error {
+        self.origin.error(self.source, position.0, message)
+    }
+
     pub fn is_at_end(&self) -> bool {
         self.remainder.is_empty()
     }
 
+    pub fn remainder(&self) -> &'src str {
+        self.remainder
+    }
+
     pub fn position(&self) -> Position {
         Position(self.offset)
     }
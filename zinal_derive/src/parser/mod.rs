@@ -2,32 +2,92 @@ use std::borrow::Cow;
 
 use self::{html::HtmlParser, input::Input};
 
+mod code;
 mod common;
+mod context;
 mod html;
+mod inheritance;
 mod input;
+mod origin;
 
-pub fn parse(source: &str) -> Result<Vec<Item<'_>>, syn::Error> {
-    let input = Input::new(source);
+pub use code::Code;
+pub use context::assign_contexts;
+pub use inheritance::{base_template, resolve_inheritance};
+pub use origin::Origin;
+
+pub fn parse(
+    source: &str,
+    origin: Origin,
+    options: ParseOptions,
+) -> Result<Vec<Item<'_>>, syn::Error> {
+    let input = Input::configured(source, origin, options);
     let mut parser = HtmlParser;
 
     parser.parse(input)
 }
 
+/// Options changing how templates are parsed.
+#[derive(Debug, Clone, Default)]
+pub struct ParseOptions {
+    /// Remove the first newline after a statement tag
+    pub trim_blocks: bool,
+    /// Remove HTML comments except for conditional comments
+    pub strip_html_comments: bool,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Item<'src> {
     Literal(Cow<'src, str>),
-    Expression(Cow<'src, str>),
+    Expression(Code<'src>, EscapeContext),
+    UnescapedExpression(Code<'src>),
     KeywordStatement {
         keyword: Keyword,
-        statement: Option<Cow<'src, str>>,
+        label: Option<Cow<'src, str>>,
+        statement: Option<Code<'src>>,
         body: Vec<Item<'src>>,
     },
-    PlainStatement(Cow<'src, str>),
+    PlainStatement(Code<'src>),
     ChildTemplate {
         name: Cow<'src, str>,
         arguments: Vec<TemplateArgument<'src>>,
         children: Vec<Item<'src>>,
+        slots: Vec<TemplateSlot<'src>>,
+    },
+    Slot {
+        name: Cow<'src, str>,
+        optional: bool,
     },
+    Extends(Code<'src>),
+    Block {
+        name: Cow<'src, str>,
+        body: Vec<Item<'src>>,
+    },
+}
+
+/// The position of an expression in the HTML output, determining how it is escaped.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum EscapeContext {
+    /// Text content, quoted attribute values and tags
+    #[default]
+    Html,
+    /// Unquoted attribute values
+    Attribute,
+    /// Script content outside of string literals
+    Script,
+    /// String literals in script content
+    ScriptString,
+    /// Style content
+    Style,
+    /// Event handler attribute values, like `onclick`, outside of string literals
+    EventHandler,
+    /// String literals in event handler attribute values
+    EventHandlerString,
+    /// Style attribute values
+    StyleAttribute,
+    /// The start of an URL attribute value
+    Url,
+    /// The rest of an URL attribute value
+    UrlPart,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -42,6 +102,8 @@ pub enum Keyword {
     Break,
     Continue,
     Let,
+    Match,
+    Case,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -54,7 +116,13 @@ pub struct TemplateArgument<'src> {
 pub enum TemplateArgumentValue<'src> {
     StrLiteral(Cow<'src, str>),
     BoolLiteral(bool),
-    Expression(Cow<'src, str>),
+    Expression(Code<'src>),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TemplateSlot<'src> {
+    pub(crate) name: Cow<'src, str>,
+    pub(crate) children: Vec<Item<'src>>,
 }
 
 impl Keyword {
@@ -67,10 +135,19 @@ impl Keyword {
                 | Keyword::For
                 | Keyword::While
                 | Keyword::Loop
+                | Keyword::Match
+                | Keyword::Case
         )
     }
 
+    pub fn is_loop(self) -> bool {
+        matches!(self, Keyword::For | Keyword::While | Keyword::Loop)
+    }
+
     pub fn is_block_terminator(self) -> bool {
-        matches!(self, Keyword::Else | Keyword::ElseIf | Keyword::End)
+        matches!(
+            self,
+            Keyword::Else | Keyword::ElseIf | Keyword::End | Keyword::Case
+        )
     }
 }
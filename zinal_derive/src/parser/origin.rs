@@ -0,0 +1,177 @@
+use proc_macro2::{Literal, Span};
+use syn::LitStr;
+
+/// Describes where a template source comes from, so errors can be located.
+#[derive(Clone)]
+pub struct Origin {
+    name: Option<String>,
+    span: Span,
+    literal: Option<Literal>,
+}
+
+impl Origin {
+    /// A template read from the file with the given display name. Errors point
+    /// to the given span, as there is no way to point into the file itself.
+    pub fn file(name: String, span: Span) -> Self {
+        Self {
+            name: Some(name),
+            span,
+            literal: None,
+        }
+    }
+
+    /// A template declared inline with the given string literal.
+    pub fn inline(literal: &LitStr) -> Self {
+        Self {
+            name: None,
+            span: literal.span(),
+            literal: Some(literal.token()),
+        }
+    }
+
+    /// The display name of the template file, if the template was read from a file.
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    /// The line and column of the given byte offset in the source, starting at 1.
+    pub fn line_column(&self, source: &str, offset: usize) -> (usize, usize) {
+        let location = Location::find(source, offset);
+        (location.line, location.column)
+    }
+
+    /// Create an error at the given byte offset in the source.
+    pub fn error(&self, source: &str, offset: usize, message: &str) -> syn::Error {
+        let location = Location::find(source, offset);
+
+        let name = self.name.as_deref().unwrap_or("inline template");
+        let line_number = location.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let indent: String = location
+            .text
+            .get(..location.column_offset)
+            .unwrap_or(location.text)
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let message = format!(
+            "{message}\n\
+             {gutter}--> {name}:{line}:{column}\n\
+             {gutter} |\n\
+             {line_number} | {text}\n\
+             {gutter} | {indent}^",
+            line = location.line,
+            column = location.column,
+            text = location.text,
+        );
+
+        let len = source
+            .get(offset..)
+            .and_then(|rest| rest.chars().next())
+            .map_or(0, char::len_utf8);
+
+        syn::Error::new(self.span_at(source, offset, len), message)
+    }
+
+    /// The most precise span available for the given range of the source.
+    pub fn span_at(&self, source: &str, offset: usize, len: usize) -> Span {
+        let Some(literal) = &self.literal else {
+            return self.span;
+        };
+
+        // Subspans are only available on nightly compilers and can only be
+        // mapped if the literal contains no escape sequences.
+        let token = literal.to_string();
+        let (prefix, suffix) = match token.strip_prefix('r') {
+            Some(raw) => {
+                let hashes = raw.len() - raw.trim_start_matches('#').len();
+                (hashes + 2, hashes + 1)
+            }
+            None => (1, 1),
+        };
+
+        if token.len() < prefix + suffix || &token[prefix..token.len() - suffix] != source {
+            return self.span;
+        }
+
+        let start = prefix + offset;
+
+        literal.subspan(start..start + len).unwrap_or(self.span)
+    }
+}
+
+impl Default for Origin {
+    fn default() -> Self {
+        Self {
+            name: None,
+            span: Span::call_site(),
+            literal: None,
+        }
+    }
+}
+
+struct Location<'src> {
+    line: usize,
+    column: usize,
+    column_offset: usize,
+    text: &'src str,
+}
+
+impl<'src> Location<'src> {
+    fn find(source: &'src str, offset: usize) -> Self {
+        let offset = offset.min(source.len());
+        let line_start = source[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = source[offset..]
+            .find('\n')
+            .map_or(source.len(), |i| offset + i);
+
+        Self {
+            line: source[..offset].matches('\n').count() + 1,
+            column: source[line_start..offset].chars().count() + 1,
+            column_offset: offset - line_start,
+            text: source[line_start..line_end].trim_end_matches('\r'),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proc_macro2::Span;
+
+    use super::Origin;
+
+    #[test]
+    fn error_location() {
+        let origin = Origin::file(String::from("templates/test.html"), Span::call_site());
+        let source = "<div>\n  <p>{{ self.name</p>\n</div>";
+
+        let error = origin.error(source, 11, "Unterminated expression");
+
+        assert_eq!(
+            error.to_string(),
+            "Unterminated expression\n \
+             --> templates/test.html:2:6\n  \
+             |\n\
+             2 |   <p>{{ self.name</p>\n  \
+             |      ^"
+        );
+    }
+
+    #[test]
+    fn error_location_at_end() {
+        let origin = Origin::default();
+        let source = "<#if x#>";
+
+        let error = origin.error(source, source.len(), "Unterminated block");
+
+        assert_eq!(
+            error.to_string(),
+            "Unterminated block\n \
+             --> inline template:1:9\n  \
+             |\n\
+             1 | <#if x#>\n  \
+             |         ^"
+        );
+    }
+}